@@ -0,0 +1,7 @@
+fn main() {
+    // Exposed as `env!("TARGET")` for the version output
+    println!(
+        "cargo:rustc-env=TARGET={}",
+        std::env::var("TARGET").unwrap_or_default()
+    );
+}
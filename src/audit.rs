@@ -0,0 +1,69 @@
+use crate::report;
+use crate::scanner::{self, ScanOptions};
+use crate::units::Units;
+use anyhow::Result;
+
+/// Runs `sukkiri audit`: scans and formats the results as text, or as JSON with `json`.
+/// Audits only use the scanners and report formatters, never the cleaner or the dashboard.
+pub fn run(options: &ScanOptions, json: bool, units: Units) -> Result<String> {
+    let results = scanner::scan_all(options)?;
+    if json {
+        Ok(report::json_summary(&results)? + "\n")
+    } else {
+        Ok(report::text_summary(&results, units))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{CategoryFilter, CategoryType};
+    use std::collections::HashSet;
+    use std::fs;
+    use std::path::Path;
+    use std::time::SystemTime;
+
+    /// Every path below `root` with its size and modification time.
+    fn snapshot(root: &Path) -> Vec<(String, u64, SystemTime)> {
+        let mut entries: Vec<_> = jwalk::WalkDir::new(root)
+            .skip_hidden(false)
+            .into_iter()
+            .flatten()
+            .map(|e| {
+                let metadata = e.metadata().unwrap();
+                (
+                    e.path().display().to_string(),
+                    metadata.len(),
+                    metadata.modified().unwrap(),
+                )
+            })
+            .collect();
+        entries.sort();
+        entries
+    }
+
+    #[test]
+    fn audit_reports_without_touching_files() -> Result<()> {
+        let projects = tempfile::tempdir()?;
+        let modules = projects.path().join("app/node_modules/left-pad");
+        fs::create_dir_all(&modules)?;
+        fs::write(modules.join("index.js"), [0; 2048])?;
+        let before = snapshot(projects.path());
+
+        let options = ScanOptions {
+            project_roots: vec![projects.path().to_path_buf()],
+            categories: CategoryFilter::Only(HashSet::from([CategoryType::NodeModules])),
+            ..ScanOptions::default()
+        };
+        let text = run(&options, false, Units::Binary)?;
+        assert!(text.starts_with("Node Modules"), "{text}");
+        assert!(text.contains("1 items"), "{text}");
+
+        let json: serde_json::Value = serde_json::from_str(&run(&options, true, Units::Binary)?)?;
+        assert_eq!(json["categories"][0]["category"], "node-modules");
+        assert_eq!(json["total_size"], 2048);
+
+        assert_eq!(snapshot(projects.path()), before);
+        Ok(())
+    }
+}
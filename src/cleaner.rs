@@ -1,33 +1,345 @@
-use crate::model::ScannedItem;
+use crate::constants::{MOVE_FREE_SPACE_RESERVE, PRIVILEGED_DIRS, TRASH_DIR};
+use crate::model::{CategoryType, ScannedItem};
+use crate::scanner::container::ContainerEngine;
+use crate::scanner::quicklook;
+use crate::undo::TrashedFile;
+use crate::units::Units;
 use anyhow::Result;
-
+use log::{info, warn};
+use serde::Deserialize;
+use std::fmt::Write as _;
+use std::io;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use sysinfo::Disks;
 
-pub fn delete_items(items: &[ScannedItem]) -> Result<()> {
-    if items.is_empty() {
-        return Ok(());
+/// How the files of a category are removed.
+/// Configured as `delete_mode = "trash" | "permanent"`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeleteMethod {
+    /// Move to the system Trash so files can still be recovered.
+    #[default]
+    Trash,
+    /// Remove immediately, bypassing the Trash.
+    Permanent,
+}
+
+impl DeleteMethod {
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Trash => Self::Permanent,
+            Self::Permanent => Self::Trash,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Trash => "Move to Trash",
+            Self::Permanent => "Delete permanently",
+        }
+    }
+}
+
+/// Returns how `category` is removed under the chosen `mode`.
+/// Moving the Trash into the Trash frees nothing, so it is always emptied permanently.
+pub fn delete_method_for(category: CategoryType, mode: DeleteMethod) -> DeleteMethod {
+    match category {
+        CategoryType::Trash => DeleteMethod::Permanent,
+        _ => mode,
+    }
+}
+
+/// What cleaning a category left behind.
+#[derive(Debug, Default)]
+pub struct CleanReport {
+    /// Paths that could not be deleted without administrator rights.
+    pub denied: Vec<PathBuf>,
+    /// Files moved to the Trash, for undoing the clean.
+    pub trashed: Vec<TrashedFile>,
+    /// Paths held open by a running app, which couldn't be deleted.
+    pub in_use: Vec<PathBuf>,
+    /// Paths left untouched because the clean was interrupted.
+    pub skipped: Vec<PathBuf>,
+}
+
+impl CleanReport {
+    /// Bytes actually freed by cleaning `items`: those left behind don't count.
+    pub fn freed(&self, items: &[ScannedItem]) -> u64 {
+        items
+            .iter()
+            .filter(|i| !self.left_behind(&i.path))
+            .map(|i| i.size)
+            .sum()
+    }
+
+    /// Whether `path` is still where it was after the clean.
+    pub fn left_behind(&self, path: &Path) -> bool {
+        self.denied.iter().any(|p| p == path)
+            || self.in_use.iter().any(|p| p == path)
+            || self.skipped.iter().any(|p| p == path)
+    }
+
+    /// Files `path` under denied or in use when `err` calls for it, so it can be
+    /// retried with `sudo` or reported as held open. Other failures are only logged.
+    fn record_failure(&mut self, path: &Path, err: &io::Error) {
+        if needs_elevation(err) {
+            self.denied.push(path.to_path_buf());
+        } else if is_in_use(err) {
+            self.in_use.push(path.to_path_buf());
+        }
+    }
+
+    /// Adds the outcome of cleaning another category.
+    pub fn append(&mut self, other: &mut Self) {
+        self.denied.append(&mut other.denied);
+        self.trashed.append(&mut other.trashed);
+        self.in_use.append(&mut other.in_use);
+        self.skipped.append(&mut other.skipped);
+    }
+}
+
+/// What a clean will do, worked out before anything is touched so it can be
+/// shown for confirmation or a dry run and then carried out.
+#[derive(Debug, Default)]
+pub struct CleanPlan {
+    /// Files and folders to move to the Trash.
+    pub trash: Vec<ScannedItem>,
+    /// Files and folders to delete permanently.
+    pub permanent: Vec<ScannedItem>,
+    /// Container images to remove with their engine (always permanent).
+    pub images: Vec<ScannedItem>,
+    /// Files and folders to move into [`Self::destination`] (`--move-to`).
+    pub moved: Vec<ScannedItem>,
+    /// Where `moved` items go, keeping their path below it.
+    pub destination: Option<PathBuf>,
+    /// Quick Look thumbnails are cleaned, so `quicklookd` must drop its state.
+    pub reset_quicklook: bool,
+    /// Once set, the clean stops after the item at hand (Ctrl-C).
+    pub stop: Arc<AtomicBool>,
+    /// How sizes are shown in errors, e.g. when the destination is too full.
+    pub units: Units,
+}
+
+impl CleanPlan {
+    /// Plans cleaning `batches` of items under the chosen delete `mode`.
+    pub fn new(batches: &[(CategoryType, Vec<ScannedItem>)], mode: DeleteMethod) -> Self {
+        let mut plan = Self::default();
+        for (category, items) in batches {
+            plan.add(items, delete_method_for(*category, mode));
+            plan.reset_quicklook |= *category == CategoryType::QuickLookCache && !items.is_empty();
+        }
+        plan
+    }
+
+    /// Plans removing `items` with `method`, regardless of category.
+    pub fn from_items(items: &[ScannedItem], method: DeleteMethod) -> Self {
+        let mut plan = Self::default();
+        plan.add(items, method);
+        plan
+    }
+
+    /// Adds `items` to be removed with `method`, regardless of category.
+    pub fn add(&mut self, items: &[ScannedItem], method: DeleteMethod) {
+        for item in items {
+            let bucket = if ContainerEngine::parse_path(&item.path).is_some() {
+                &mut self.images
+            } else {
+                match method {
+                    DeleteMethod::Trash => &mut self.trash,
+                    DeleteMethod::Permanent => &mut self.permanent,
+                }
+            };
+            bucket.push(item.clone());
+        }
     }
 
+    /// Stops the clean after the current item once `stop` is set.
+    pub fn with_stop(mut self, stop: Arc<AtomicBool>) -> Self {
+        self.stop = stop;
+        self
+    }
+
+    /// Shows sizes in `units` when refusing the clean.
+    pub fn with_units(mut self, units: Units) -> Self {
+        self.units = units;
+        self
+    }
+
+    /// Moves the files and folders into `dir` instead of trashing or deleting
+    /// them. Container images are still removed. Does nothing without a `dir`.
+    pub fn with_destination(mut self, dir: Option<PathBuf>) -> Self {
+        if let Some(dir) = dir {
+            self.moved.append(&mut self.trash);
+            self.moved.append(&mut self.permanent);
+            self.destination = Some(dir);
+        }
+        self
+    }
+
+    /// Every planned item.
+    pub fn items(&self) -> impl Iterator<Item = &ScannedItem> {
+        self.trash
+            .iter()
+            .chain(&self.permanent)
+            .chain(&self.images)
+            .chain(&self.moved)
+    }
+
+    /// One line per kind of removal, e.g. `Move 3 items (1.2 GiB) to the Trash`.
+    pub fn describe(&self, units: Units) -> String {
+        let mut out = String::new();
+        let size = |items: &[ScannedItem]| units.format(items.iter().map(|i| i.size).sum());
+        let destination = self
+            .destination
+            .as_ref()
+            .map(|dir| format!(" to {}", dir.display()))
+            .unwrap_or_default();
+        for (items, action, noun, suffix) in [
+            (&self.trash, "Move", "items", " to the Trash"),
+            (&self.permanent, "Delete", "items", " permanently"),
+            (&self.images, "Remove", "container images", ""),
+            (&self.moved, "Move", "items", destination.as_str()),
+        ] {
+            if !items.is_empty() {
+                let _ = writeln!(
+                    out,
+                    "{action} {} {noun} ({}){suffix}",
+                    items.len(),
+                    size(items)
+                );
+            }
+        }
+        out
+    }
+
+    /// Refuses a move into [`Self::destination`] that would fill its volume, before
+    /// anything is touched. Only items on other volumes need space there; the rest are renamed.
+    pub fn ensure_room(&self) -> Result<()> {
+        let Some(dir) = &self.destination else {
+            return Ok(());
+        };
+        // The destination may not exist yet, so look at the part that does
+        let Some(existing) = dir.ancestors().find(|path| path.exists()) else {
+            return Ok(());
+        };
+        let device = existing.metadata()?.dev();
+        let needed: u64 = self
+            .moved
+            .iter()
+            .filter(|item| {
+                item.path
+                    .symlink_metadata()
+                    .is_ok_and(|metadata| metadata.dev() != device)
+            })
+            .map(|item| item.size)
+            .sum();
+        if needed == 0 {
+            return Ok(());
+        }
+        match available_space(existing) {
+            Some(available) if !has_room(needed, available) => Err(anyhow::anyhow!(
+                "Not enough space in {}: moving needs {} but only {} is free",
+                dir.display(),
+                self.units.format(needed),
+                self.units.format(available)
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Carries out the plan, calling `progress_cb` with each item once it's handled.
+    /// With `dry_run` every step is only logged and nothing is touched.
+    pub fn execute(
+        &self,
+        dry_run: bool,
+        progress_cb: Option<&dyn Fn(&ScannedItem)>,
+    ) -> Result<CleanReport> {
+        let report_progress = |items: &[ScannedItem]| {
+            if let Some(cb) = progress_cb {
+                items.iter().for_each(cb);
+            }
+        };
+        if dry_run {
+            for item in self.items() {
+                info!("Would clean {}", item.path.display());
+            }
+            report_progress(&self.images);
+            report_progress(&self.trash);
+            report_progress(&self.permanent);
+            report_progress(&self.moved);
+            return Ok(CleanReport::default());
+        }
+
+        self.ensure_room()?;
+        let (images, _) = partition_items(&self.images);
+        remove_container_images(&images)?;
+        report_progress(&self.images);
+        let mut report = move_to_trash(&self.trash, &self.stop)?;
+        report_progress(&self.trash);
+        report.append(&mut delete_items(&self.permanent, &self.stop)?);
+        report_progress(&self.permanent);
+        if let Some(dir) = &self.destination {
+            report.append(&mut move_items_to(&self.moved, dir, &self.stop)?);
+            report_progress(&self.moved);
+        }
+
+        // quicklookd keeps cache state in memory, so have it rebuild from scratch
+        if self.reset_quicklook {
+            quicklook::reset_quicklook_cache();
+        }
+        Ok(report)
+    }
+}
+
+/// Whether a failed deletion could succeed with administrator rights.
+pub fn needs_elevation(err: &io::Error) -> bool {
+    err.kind() == io::ErrorKind::PermissionDenied
+}
+
+/// Whether a deletion failed because a running app holds the file open (`EBUSY`, `ETXTBSY`).
+pub fn is_in_use(err: &io::Error) -> bool {
+    matches!(
+        err.kind(),
+        io::ErrorKind::ResourceBusy | io::ErrorKind::ExecutableFileBusy
+    )
+}
+
+/// Whether `path` lives in a root-owned system location such as `/Library/Caches`.
+pub fn is_privileged_path(path: &Path) -> bool {
+    PRIVILEGED_DIRS.iter().any(|dir| path.starts_with(dir))
+}
+
+/// Whether sukkiri runs as root (effective user ID 0), e.g. under `sudo`.
+pub fn is_root() -> bool {
+    nix::unistd::geteuid().is_root()
+}
+
+/// Splits items into container images and real filesystem paths.
+fn partition_items(items: &[ScannedItem]) -> (Vec<(ContainerEngine, String)>, Vec<&Path>) {
     let mut file_paths = Vec::new();
-    let mut docker_ids = Vec::new();
+    let mut images = Vec::new();
 
     for item in items {
-        let path_str = item.path.to_string_lossy();
-        if path_str.starts_with("docker://") {
-            // Format: docker://<ID>/<Name>
-            if let Some(rest) = path_str.strip_prefix("docker://") {
-                // Extract ID (part before the first slash)
-                let id = rest.split('/').next().unwrap_or(rest);
-                docker_ids.push(id.to_string());
-            }
-        } else {
-            file_paths.push(&item.path);
+        // Format: <engine>://<ID>/<Name>
+        match ContainerEngine::parse_path(&item.path) {
+            Some(image) => images.push(image),
+            None => file_paths.push(item.path.as_path()),
         }
     }
 
-    // 1. Delete Docker images (Permanent!)
-    for id in docker_ids {
-        let output = Command::new("docker").args(["rmi", &id]).output();
+    (images, file_paths)
+}
+
+/// Removes container images with the engine that listed them. This is always permanent!
+fn remove_container_images(images: &[(ContainerEngine, String)]) -> Result<()> {
+    for (engine, id) in images {
+        let binary = engine.binary();
+        info!("Removing {binary} image {id}");
+        let output = Command::new(binary).args(["rmi", id]).output();
 
         match output {
             Ok(out) => {
@@ -35,28 +347,301 @@ pub fn delete_items(items: &[ScannedItem]) -> Result<()> {
                     let stdout = String::from_utf8_lossy(&out.stdout);
                     let stderr = String::from_utf8_lossy(&out.stderr);
                     return Err(anyhow::anyhow!(
-                        "Failed to remove Docker image {id}.\nStdout: {stdout}\nStderr: {stderr}"
+                        "Failed to remove {binary} image {id}.\nStdout: {stdout}\nStderr: {stderr}"
                     ));
                 }
             }
-            Err(e) => return Err(anyhow::anyhow!("Failed to execute docker rmi: {e}")),
+            Err(e) => return Err(anyhow::anyhow!("Failed to execute {binary} rmi: {e}")),
         }
     }
+    Ok(())
+}
 
-    // 2. Permanently delete files
-    if !file_paths.is_empty() {
-        for path in file_paths {
-            if path.is_dir() {
-                let _ = std::fs::remove_dir_all(path);
-            } else {
-                let _ = std::fs::remove_file(path);
+/// Moves items to the Trash, returning where each file ended up.
+pub fn move_to_trash(items: &[ScannedItem], stop: &AtomicBool) -> Result<CleanReport> {
+    let trash_dir = crate::home::resolve_home()
+        .map(|home| home.join(TRASH_DIR))
+        .filter(|dir| dir.is_dir());
+    move_to_trash_in(items, trash_dir.as_deref(), stop)
+}
+
+fn move_to_trash_in(
+    items: &[ScannedItem],
+    trash_dir: Option<&Path>,
+    stop: &AtomicBool,
+) -> Result<CleanReport> {
+    let mut report = CleanReport::default();
+    if items.is_empty() {
+        return Ok(report);
+    }
+
+    let (images, file_paths) = partition_items(items);
+
+    // 1. Delete container images (they have no Trash)
+    remove_container_images(&images)?;
+
+    // 2. Move files into the Trash directly, so we know where they went
+    let mut leftover = Vec::new();
+    for path in file_paths {
+        if stop.load(Ordering::Relaxed) {
+            report.skipped.push(path.to_path_buf());
+            continue;
+        }
+        match trash_dir.map(|dir| rename_into(path, dir)) {
+            Some(Ok(file)) => report.trashed.push(file),
+            // The system Trash would be refused just the same
+            Some(Err(e)) if needs_elevation(&e) || is_in_use(&e) => {
+                warn!("Failed to move {} to the Trash: {e}", path.display());
+                report.record_failure(path, &e);
+            }
+            Some(Err(e)) => {
+                // Other volumes have their own Trash, which the system knows best
+                info!("Can't move {} into the Trash: {e}", path.display());
+                leftover.push(path);
             }
+            None => leftover.push(path),
         }
     }
+    if !report.trashed.is_empty() {
+        info!("Moved {} items to the Trash", report.trashed.len());
+    }
 
+    // 3. Let the system trash the rest (not undoable from sukkiri), one at a time
+    // so a refused item doesn't stop the others. It can't trash links to nothing,
+    // which are worthless anyway, so those are removed.
+    for path in leftover {
+        if stop.load(Ordering::Relaxed) {
+            report.skipped.push(path.to_path_buf());
+            continue;
+        }
+        if is_broken_symlink(path) {
+            info!("Removing broken link {}", path.display());
+            if let Err(e) = std::fs::remove_file(path) {
+                warn!("Failed to remove {}: {e}", path.display());
+                report.record_failure(path, &e);
+            }
+            continue;
+        }
+        info!("Moving {} to the Trash", path.display());
+        if let Err(e) = trash::delete(path) {
+            warn!("Failed to move {} to the Trash: {e}", path.display());
+            if let Some(e) = trash_io_error(&e) {
+                report.record_failure(path, &e);
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// The filesystem error behind a failed system trash, where the platform reports one.
+fn trash_io_error(err: &trash::Error) -> Option<io::Error> {
+    #[cfg(all(unix, not(target_os = "macos")))]
+    if let trash::Error::FileSystem { source, .. } = err {
+        return Some(io::Error::from(source.kind()));
+    }
+    let _ = err;
+    None
+}
+
+/// Renames `path` into `trash_dir`, numbering the name if it is taken.
+fn rename_into(path: &Path, trash_dir: &Path) -> io::Result<TrashedFile> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidInput))?;
+    let destination = unused_path(&trash_dir.join(name));
+    std::fs::rename(path, &destination)?;
+    Ok(TrashedFile {
+        original: path.to_path_buf(),
+        trashed: destination,
+    })
+}
+
+/// Whether `needed` bytes fit on a volume with `available` bytes free while
+/// still leaving [`MOVE_FREE_SPACE_RESERVE`] free.
+pub fn has_room(needed: u64, available: u64) -> bool {
+    needed
+        .checked_add(MOVE_FREE_SPACE_RESERVE)
+        .is_some_and(|total| total <= available)
+}
+
+/// Free space on the volume holding `path`, the one mounted deepest above it.
+fn available_space(path: &Path) -> Option<u64> {
+    let path = path.canonicalize().ok()?;
+    Disks::new_with_refreshed_list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(sysinfo::Disk::available_space)
+}
+
+/// `path`, or if that is taken the first free numbered name next to it (`report 2.txt`).
+fn unused_path(path: &Path) -> PathBuf {
+    let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+        return path.to_path_buf();
+    };
+    let stem = Path::new(name)
+        .file_stem()
+        .unwrap_or(name)
+        .to_string_lossy();
+    let extension = Path::new(name)
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+    let mut destination = path.to_path_buf();
+    let mut n = 2;
+    while destination.symlink_metadata().is_ok() {
+        destination = dir.join(format!("{stem} {n}{extension}"));
+        n += 1;
+    }
+    destination
+}
+
+/// Moves items into `dir`, keeping their full path below it
+/// (`/Users/me/Library/Caches/app` ends up in `dir/Users/me/Library/Caches/app`).
+/// Taken names are numbered. Reports the paths that couldn't be moved like [`delete_items`].
+pub fn move_items_to(items: &[ScannedItem], dir: &Path, stop: &AtomicBool) -> Result<CleanReport> {
+    let mut report = CleanReport::default();
+    if items.is_empty() {
+        return Ok(report);
+    }
+    std::fs::create_dir_all(dir)?;
+
+    for item in items {
+        let path = item.path.as_path();
+        if stop.load(Ordering::Relaxed) {
+            report.skipped.push(path.to_path_buf());
+            continue;
+        }
+        let target = dir.join(path.strip_prefix("/").unwrap_or(path));
+        let result = target
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| relocate(path, &unused_path(&target)));
+        match result {
+            Ok(destination) => info!("Moved {} to {}", path.display(), destination.display()),
+            Err(e) => {
+                warn!("Failed to move {}: {e}", path.display());
+                report.record_failure(path, &e);
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Renames `path` to `destination`, or copies and then removes it when they
+/// are on different volumes (e.g. an external drive).
+fn relocate(path: &Path, destination: &Path) -> io::Result<PathBuf> {
+    match std::fs::rename(path, destination) {
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+            if let Err(e) = copy_all(path, destination) {
+                // Don't leave half a copy behind
+                let _ = remove_path(destination);
+                return Err(e);
+            }
+            remove_path(path)?;
+        }
+        result => result?,
+    }
+    Ok(destination.to_path_buf())
+}
+
+/// Copies a file, link or folder with everything in it. Links are copied, not followed.
+fn copy_all(from: &Path, to: &Path) -> io::Result<()> {
+    let metadata = from.symlink_metadata()?;
+    if metadata.is_dir() {
+        std::fs::create_dir(to)?;
+        for entry in std::fs::read_dir(from)? {
+            let entry = entry?;
+            copy_all(&entry.path(), &to.join(entry.file_name()))?;
+        }
+    } else if metadata.file_type().is_symlink() {
+        std::os::unix::fs::symlink(std::fs::read_link(from)?, to)?;
+    } else {
+        std::fs::copy(from, to)?;
+    }
     Ok(())
 }
 
+/// Removes a file, link or folder. Links are removed themselves, even if broken.
+fn remove_path(path: &Path) -> io::Result<()> {
+    match path.symlink_metadata() {
+        Ok(metadata) if metadata.is_dir() => std::fs::remove_dir_all(path),
+        _ => std::fs::remove_file(path),
+    }
+}
+
+/// Permanently deletes items, stopping after the current one once `stop` is set.
+/// Reports the paths that failed for lack of permissions and those held open by running apps.
+pub fn delete_items(items: &[ScannedItem], stop: &AtomicBool) -> Result<CleanReport> {
+    let mut report = CleanReport::default();
+    if items.is_empty() {
+        return Ok(report);
+    }
+
+    let (images, file_paths) = partition_items(items);
+
+    // 1. Delete container images (Permanent!)
+    remove_container_images(&images)?;
+
+    // 2. Permanently delete files
+    for path in file_paths {
+        if stop.load(Ordering::Relaxed) {
+            report.skipped.push(path.to_path_buf());
+            continue;
+        }
+        info!("Deleting {}", path.display());
+        if let Err(e) = remove_path(path) {
+            warn!("Failed to delete {}: {e}", path.display());
+            report.record_failure(path, &e);
+        }
+    }
+
+    Ok(report)
+}
+
+/// Whether `path` is a symbolic link whose target doesn't exist.
+fn is_broken_symlink(path: &Path) -> bool {
+    path.symlink_metadata()
+        .is_ok_and(|m| m.file_type().is_symlink())
+        && !path.exists()
+}
+
+/// Deletes `paths` as root. macOS asks for an administrator password first.
+pub fn delete_with_admin_privileges(paths: &[PathBuf]) -> Result<()> {
+    if paths.is_empty() {
+        return Ok(());
+    }
+    info!(
+        "Deleting {} items with administrator privileges",
+        paths.len()
+    );
+    let output = Command::new("osascript")
+        .args(["-e", &admin_delete_script(paths)])
+        .output()?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Builds the `osascript` program that runs `rm -rf` on `paths` with administrator privileges.
+fn admin_delete_script(paths: &[PathBuf]) -> String {
+    let command = paths
+        .iter()
+        .fold("rm -rf".to_string(), |mut command, path| {
+            // Single-quote for the shell, where `'` becomes `'\''`
+            let quoted = path.to_string_lossy().replace('\'', r"'\''");
+            let _ = write!(command, " '{quoted}'");
+            command
+        });
+    let escaped = command.replace('\\', r"\\").replace('"', r#"\""#);
+    format!("do shell script \"{escaped}\" with administrator privileges")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -65,6 +650,183 @@ mod tests {
     use std::time::SystemTime;
     use tempfile::tempdir;
 
+    fn item(path: &Path, size: u64) -> ScannedItem {
+        ScannedItem {
+            path: path.to_path_buf(),
+            size,
+            modified: SystemTime::now(),
+        }
+    }
+
+    #[test]
+    fn plan_sorts_items_into_buckets() {
+        let paths = |items: &[ScannedItem]| -> Vec<PathBuf> {
+            items.iter().map(|i| i.path.clone()).collect()
+        };
+        let batches = vec![
+            (
+                CategoryType::UserCache,
+                vec![item(Path::new("/cache/app"), 10)],
+            ),
+            (
+                CategoryType::Trash,
+                vec![item(Path::new("/Users/test/.Trash/old"), 20)],
+            ),
+            (
+                CategoryType::DockerImages,
+                vec![item(Path::new("docker://abc123/app:old"), 30)],
+            ),
+            (
+                CategoryType::QuickLookCache,
+                vec![item(Path::new("/cache/thumbnails.data"), 40)],
+            ),
+        ];
+
+        let plan = CleanPlan::new(&batches, DeleteMethod::Trash);
+        assert_eq!(
+            paths(&plan.trash),
+            vec![
+                PathBuf::from("/cache/app"),
+                PathBuf::from("/cache/thumbnails.data")
+            ]
+        );
+        assert_eq!(
+            paths(&plan.permanent),
+            vec![PathBuf::from("/Users/test/.Trash/old")]
+        );
+        assert_eq!(
+            paths(&plan.images),
+            vec![PathBuf::from("docker://abc123/app:old")]
+        );
+        assert!(plan.reset_quicklook);
+        assert_eq!(
+            plan.describe(Units::Binary),
+            "Move 2 items (50 B) to the Trash\n\
+             Delete 1 items (20 B) permanently\n\
+             Remove 1 container images (30 B)\n"
+        );
+    }
+
+    #[test]
+    fn moves_items_below_the_destination() -> Result<()> {
+        let dir = tempdir()?;
+        let cache = dir.path().join("Caches/app");
+        std::fs::create_dir_all(cache.join("data"))?;
+        std::fs::write(cache.join("data/blob"), b"cached")?;
+        let log = dir.path().join("old.log");
+        std::fs::write(&log, b"log")?;
+        let target = dir.path().join("staging");
+        let kept = target.join(dir.path().strip_prefix("/")?);
+        // Left over from an earlier move
+        std::fs::create_dir_all(&kept)?;
+        std::fs::write(kept.join("old.log"), b"earlier")?;
+
+        let plan = CleanPlan::new(
+            &[(CategoryType::UserLogs, vec![item(&cache, 6), item(&log, 3)])],
+            DeleteMethod::Permanent,
+        )
+        .with_destination(Some(target.clone()));
+        assert!(plan.trash.is_empty() && plan.permanent.is_empty());
+        assert_eq!(
+            plan.describe(Units::Binary),
+            format!("Move 2 items (9 B) to {}\n", target.display())
+        );
+        let report = plan.execute(false, None)?;
+
+        assert!(report.skipped.is_empty() && report.denied.is_empty());
+        assert!(!cache.exists() && !log.exists());
+        assert_eq!(std::fs::read(kept.join("Caches/app/data/blob"))?, b"cached");
+        assert_eq!(std::fs::read(kept.join("old 2.log"))?, b"log");
+        assert_eq!(std::fs::read(kept.join("old.log"))?, b"earlier");
+        Ok(())
+    }
+
+    #[test]
+    fn moves_need_room_on_the_destination() -> Result<()> {
+        let gib = 1 << 30;
+        assert!(has_room(gib, 3 * gib));
+        assert!(has_room(2 * gib, 3 * gib));
+        assert!(!has_room(2 * gib + 1, 3 * gib));
+        assert!(!has_room(0, MOVE_FREE_SPACE_RESERVE - 1));
+        assert!(!has_room(u64::MAX, u64::MAX));
+
+        // Renames within a volume need no space, so neither does moving within a temp dir
+        let dir = tempdir()?;
+        let file = dir.path().join("big.iso");
+        File::create(&file)?;
+        let plan = CleanPlan::from_items(&[item(&file, u64::MAX)], DeleteMethod::Trash)
+            .with_destination(Some(dir.path().join("staging")));
+        plan.ensure_room()
+    }
+
+    #[test]
+    fn copies_folders_with_their_links() -> Result<()> {
+        use std::os::unix::fs::symlink;
+
+        let dir = tempdir()?;
+        let from = dir.path().join("from");
+        std::fs::create_dir_all(from.join("sub"))?;
+        std::fs::write(from.join("sub/file"), b"data")?;
+        symlink("missing", from.join("link"))?;
+
+        let to = dir.path().join("to");
+        copy_all(&from, &to)?;
+        assert_eq!(std::fs::read(to.join("sub/file"))?, b"data");
+        assert_eq!(std::fs::read_link(to.join("link"))?, Path::new("missing"));
+        Ok(())
+    }
+
+    #[test]
+    fn dry_run_touches_nothing() -> Result<()> {
+        let dir = tempdir()?;
+        let trashed = dir.path().join("cache");
+        std::fs::create_dir(&trashed)?;
+        let deleted = dir.path().join("old.log");
+        File::create(&deleted)?;
+        let moved = dir.path().join("build.log");
+        File::create(&moved)?;
+
+        let mut plan = CleanPlan::default();
+        plan.add(&[item(&trashed, 1)], DeleteMethod::Trash);
+        plan.add(&[item(&deleted, 2)], DeleteMethod::Permanent);
+        plan.moved.push(item(&moved, 3));
+        plan.destination = Some(dir.path().join("staging"));
+        let seen = std::sync::Mutex::new(Vec::new());
+        let report = plan.execute(
+            true,
+            Some(&|i: &ScannedItem| {
+                seen.lock().unwrap().push(i.path.clone());
+            }),
+        )?;
+
+        assert!(trashed.exists());
+        assert!(deleted.exists());
+        assert!(moved.exists() && !dir.path().join("staging").exists());
+        assert!(report.trashed.is_empty() && report.denied.is_empty());
+        assert_eq!(seen.into_inner().unwrap(), vec![trashed, deleted, moved]);
+        Ok(())
+    }
+
+    #[test]
+    fn stopped_clean_skips_the_remaining_items() -> Result<()> {
+        let dir = tempdir()?;
+        let trash_dir = dir.path().join(".Trash");
+        std::fs::create_dir(&trash_dir)?;
+        let kept = dir.path().join("kept.log");
+        File::create(&kept)?;
+
+        let stop = AtomicBool::new(true);
+        let items = [item(&kept, 5)];
+        let mut report = delete_items(&items, &stop)?;
+        report.append(&mut move_to_trash_in(&items, Some(&trash_dir), &stop)?);
+
+        assert!(kept.exists());
+        assert!(report.trashed.is_empty());
+        assert_eq!(report.skipped, vec![kept.clone(), kept]);
+        assert_eq!(report.freed(&items), 0);
+        Ok(())
+    }
+
     #[test]
     fn permanent_delete_logic() -> Result<()> {
         let dir = tempdir()?;
@@ -79,16 +841,287 @@ mod tests {
             modified: SystemTime::now(),
         };
 
-        delete_items(&[item])?;
+        let report = delete_items(&[item], &AtomicBool::new(false))?;
+
+        assert!(!file_path.exists());
+        assert!(report.denied.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn deletes_links_and_empty_files() -> Result<()> {
+        use std::os::unix::fs::symlink;
+        let dir = tempdir()?;
+        let broken = dir.path().join("broken");
+        symlink(dir.path().join("missing"), &broken)?;
+        let target = dir.path().join("target");
+        std::fs::create_dir(&target)?;
+        File::create(target.join("keep.txt"))?;
+        let linked_dir = dir.path().join("linked");
+        symlink(&target, &linked_dir)?;
+        let empty = dir.path().join("empty.log");
+        File::create(&empty)?;
+
+        let items = [item(&broken, 0), item(&linked_dir, 0), item(&empty, 0)];
+        let report = delete_items(&items, &AtomicBool::new(false))?;
+
+        assert!(broken.symlink_metadata().is_err());
+        assert!(linked_dir.symlink_metadata().is_err());
+        assert!(!empty.exists());
+        assert!(target.join("keep.txt").exists());
+        assert!(report.denied.is_empty() && report.in_use.is_empty());
+        assert_eq!(report.freed(&items), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn broken_links_are_removed_instead_of_trashed() -> Result<()> {
+        use std::os::unix::fs::symlink;
+        let dir = tempdir()?;
+        let broken = dir.path().join("broken");
+        symlink(dir.path().join("missing"), &broken)?;
+
+        // Without a Trash folder of our own, the system Trash would be asked
+        let report = move_to_trash_in(&[item(&broken, 0)], None, &AtomicBool::new(false))?;
+        assert!(report.trashed.is_empty());
+        assert!(broken.symlink_metadata().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn trash_leaves_denied_paths_for_a_retry() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+        // Permissions don't stop root
+        if is_root() {
+            return Ok(());
+        }
+        let dir = tempdir()?;
+        let trash_dir = dir.path().join(".Trash");
+        std::fs::create_dir(&trash_dir)?;
+        let locked = dir.path().join("locked");
+        std::fs::create_dir(&locked)?;
+        let denied = locked.join("cache.bin");
+        File::create(&denied)?;
+        let free = dir.path().join("free.bin");
+        File::create(&free)?;
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o555))?;
+
+        let items = [item(&denied, 10), item(&free, 5)];
+        let report = move_to_trash_in(&items, Some(&trash_dir), &AtomicBool::new(false));
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o755))?;
+
+        let report = report?;
+        assert_eq!(report.denied, vec![denied.clone()]);
+        assert!(denied.exists() && !free.exists());
+        assert_eq!(report.freed(&items), 5);
+        Ok(())
+    }
+
+    #[test]
+    fn failures_are_sorted_for_a_retry() {
+        let mut report = CleanReport::default();
+        let path = Path::new("/Library/Caches/locked");
+        report.record_failure(path, &io::Error::from(io::ErrorKind::PermissionDenied));
+        report.record_failure(path, &io::Error::from(io::ErrorKind::ResourceBusy));
+        report.record_failure(path, &io::Error::from(io::ErrorKind::NotFound));
+        assert_eq!(report.denied, vec![path.to_path_buf()]);
+        assert_eq!(report.in_use, vec![path.to_path_buf()]);
+    }
 
+    #[test]
+    fn trash_moves_can_be_undone() -> Result<()> {
+        let dir = tempdir()?;
+        let trash_dir = dir.path().join(".Trash");
+        std::fs::create_dir(&trash_dir)?;
+        File::create(trash_dir.join("notes.txt"))?;
+        let file_path = dir.path().join("notes.txt");
+        File::create(&file_path)?;
+
+        let item = ScannedItem {
+            path: file_path.clone(),
+            size: 0,
+            modified: SystemTime::now(),
+        };
+        let trashed = move_to_trash_in(&[item], Some(&trash_dir), &AtomicBool::new(false))?.trashed;
+
+        assert_eq!(
+            trashed,
+            vec![TrashedFile {
+                original: file_path.clone(),
+                trashed: trash_dir.join("notes 2.txt"),
+            }]
+        );
         assert!(!file_path.exists());
+
+        assert!(crate::undo::restore(trashed).is_empty());
+        assert!(file_path.exists());
         Ok(())
     }
 
     #[test]
     fn move_to_trash_empty_list() -> Result<()> {
         let items: Vec<ScannedItem> = vec![];
-        delete_items(&items)?;
+        move_to_trash(&items, &AtomicBool::new(false))?;
+        delete_items(&items, &AtomicBool::new(false))?;
         Ok(())
     }
+
+    #[test]
+    fn trash_category_is_emptied_permanently() {
+        for mode in [DeleteMethod::Trash, DeleteMethod::Permanent] {
+            assert_eq!(
+                delete_method_for(CategoryType::Trash, mode),
+                DeleteMethod::Permanent
+            );
+        }
+        for category in [
+            CategoryType::Downloads,
+            CategoryType::UserCache,
+            CategoryType::NodeModules,
+        ] {
+            assert_eq!(
+                delete_method_for(category, DeleteMethod::Trash),
+                DeleteMethod::Trash
+            );
+            assert_eq!(
+                delete_method_for(category, DeleteMethod::Permanent),
+                DeleteMethod::Permanent
+            );
+        }
+    }
+
+    #[test]
+    fn permanent_mode_deletes_instead_of_trashing() -> Result<()> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join("cache.bin");
+        File::create(&file_path)?;
+
+        let item = ScannedItem {
+            path: file_path.clone(),
+            size: 0,
+            modified: SystemTime::now(),
+        };
+        CleanPlan::new(
+            &[(CategoryType::UserCache, vec![item])],
+            DeleteMethod::Permanent,
+        )
+        .execute(false, None)?;
+
+        assert!(!file_path.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn delete_mode_toggles() {
+        assert_eq!(DeleteMethod::default(), DeleteMethod::Trash);
+        assert_eq!(DeleteMethod::Trash.toggled(), DeleteMethod::Permanent);
+        assert_eq!(DeleteMethod::Permanent.toggled(), DeleteMethod::Trash);
+    }
+
+    #[test]
+    fn clean_trash_category_removes_files() -> Result<()> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join("trashed.txt");
+        File::create(&file_path)?;
+
+        let item = ScannedItem {
+            path: file_path.clone(),
+            size: 0,
+            modified: SystemTime::now(),
+        };
+        CleanPlan::new(&[(CategoryType::Trash, vec![item])], DeleteMethod::Trash)
+            .execute(false, None)?;
+
+        assert!(!file_path.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn permission_denied_needs_elevation() {
+        assert!(needs_elevation(&io::Error::from(
+            io::ErrorKind::PermissionDenied
+        )));
+        assert!(!needs_elevation(&io::Error::from(io::ErrorKind::NotFound)));
+    }
+
+    #[test]
+    fn busy_files_are_in_use() {
+        assert!(is_in_use(&io::Error::from(io::ErrorKind::ResourceBusy)));
+        // EBUSY and ETXTBSY have the same numbers on macOS and Linux
+        assert!(is_in_use(&io::Error::from_raw_os_error(16)));
+        assert!(is_in_use(&io::Error::from_raw_os_error(26)));
+        assert!(!is_in_use(&io::Error::from(
+            io::ErrorKind::PermissionDenied
+        )));
+
+        let report = CleanReport {
+            in_use: vec![PathBuf::from("/cache/open.db")],
+            ..CleanReport::default()
+        };
+        let item = |path: &str, size| ScannedItem {
+            path: PathBuf::from(path),
+            size,
+            modified: SystemTime::now(),
+        };
+        let items = [item("/cache/open.db", 100), item("/cache/old.db", 5)];
+        assert_eq!(report.freed(&items), 5);
+    }
+
+    #[test]
+    fn classifies_privileged_paths() {
+        assert!(is_privileged_path(Path::new(
+            "/Library/Caches/com.apple.iconservices"
+        )));
+        assert!(is_privileged_path(Path::new("/private/var/log/system.log")));
+        assert!(!is_privileged_path(Path::new(
+            "/Users/test/Library/Caches/foo"
+        )));
+        assert!(!is_privileged_path(Path::new("/Libraryish/foo")));
+        assert!(!is_privileged_path(Path::new(
+            "docker://abc123/<none>:<none>"
+        )));
+    }
+
+    #[test]
+    fn admin_delete_script_quotes_paths() {
+        let paths = vec![
+            PathBuf::from("/Library/Caches/com.example"),
+            PathBuf::from("/private/var/log/it's \"here\""),
+        ];
+        assert_eq!(
+            admin_delete_script(&paths),
+            r#"do shell script "rm -rf '/Library/Caches/com.example' '/private/var/log/it'\\''s \"here\"'" with administrator privileges"#
+        );
+    }
+
+    #[test]
+    fn partition_images_and_files() {
+        let items = vec![
+            ScannedItem {
+                path: "docker://abc123/<none>:<none>".into(),
+                size: 0,
+                modified: SystemTime::now(),
+            },
+            ScannedItem {
+                path: "podman://def456/<none>:<none>".into(),
+                size: 0,
+                modified: SystemTime::now(),
+            },
+            ScannedItem {
+                path: "/tmp/file".into(),
+                size: 0,
+                modified: SystemTime::now(),
+            },
+        ];
+
+        let (images, file_paths) = partition_items(&items);
+        assert_eq!(
+            images,
+            vec![
+                (ContainerEngine::Docker, "abc123".to_string()),
+                (ContainerEngine::Podman, "def456".to_string())
+            ]
+        );
+        assert_eq!(file_paths, vec![Path::new("/tmp/file")]);
+    }
 }
@@ -0,0 +1,716 @@
+use crate::allowlist::Allowlist;
+use crate::cleaner::{CleanPlan, CleanReport, DeleteMethod};
+use crate::config::{self, Config};
+use crate::model::{CategoryFilter, CategoryType, Profile, Risk, ScanResult, ScannedItem};
+use crate::scanner::{self, ScanOptions, explain};
+use crate::ui::app::App;
+use crate::ui::theme::{Theme, ThemePreset};
+use crate::units::Units;
+use crate::{audit, constants, diff, home, interrupt, logging, pins, report, stats, ui, undo};
+use anyhow::Result;
+use clap::{ArgGroup, Parser, Subcommand};
+use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+};
+use ratatui::prelude::*;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Parser)]
+#[allow(clippy::struct_excessive_bools)]
+#[command(version = constants::VERSION_INFO, about, long_about = None, disable_version_flag = true)]
+#[command(group = ArgGroup::new("unattended").args(["yes", "print_paths"]))]
+struct Cli {
+    /// Print version information
+    #[arg(short = 'v', long = "version", action = clap::ArgAction::Version)]
+    version: Option<bool>,
+
+    /// Color theme preset (overrides the `[theme]` preset in config.toml)
+    #[arg(long, value_enum)]
+    theme: Option<ThemePreset>,
+
+    /// How to display sizes (overrides `units` in config.toml)
+    #[arg(long, value_enum)]
+    units: Option<Units>,
+
+    /// Don't cross filesystem boundaries while scanning (like `du -x`)
+    #[arg(short = 'x', long)]
+    one_file_system: bool,
+
+    /// Directory to search for `node_modules` (repeatable, default: ~/Projects)
+    #[arg(long = "projects-root", value_name = "PATH")]
+    projects_roots: Vec<PathBuf>,
+
+    /// How many levels below each projects root to search
+    #[arg(long, value_name = "N")]
+    max_depth: Option<usize>,
+
+    /// Seconds each category may scan before showing partial results (0: no limit)
+    #[arg(long, value_name = "SECONDS", default_value_t = constants::SCAN_TIME_BUDGET_SECS)]
+    time_budget: u64,
+
+    /// Leave out items smaller than SIZE (e.g. 100MB)
+    #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+    min_size: Option<u64>,
+
+    /// Leave out items modified within the last DAYS days
+    #[arg(long, value_name = "DAYS")]
+    min_age: Option<u64>,
+
+    /// Only read the filesystem: skip Docker/Podman and other command-based scans
+    #[arg(long, visible_alias = "no-docker")]
+    no_external_commands: bool,
+
+    /// Leave caches that running apps have open unselected (runs `lsof`)
+    #[arg(long, conflicts_with = "no_external_commands")]
+    skip_open_files: bool,
+
+    /// Flag identical large files in the top items view (reads their contents)
+    #[arg(long)]
+    duplicates: bool,
+
+    /// Print a plain-text summary instead of opening the dashboard
+    #[arg(long, conflicts_with_all = ["free", "yes"])]
+    summary: bool,
+
+    /// Write a self-contained HTML report of the scan to FILE instead of opening the dashboard
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["free", "summary", "yes", "undo_last"])]
+    html: Option<PathBuf>,
+
+    /// Tell why PATH would be left out of the scan (allowlist rule, unreadable, ...)
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["free", "summary", "yes", "undo_last", "html"])]
+    explain: Option<PathBuf>,
+
+    /// Compare with a report saved by `sukkiri audit --json` and print what grew or shrank
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["free", "summary", "yes", "undo_last", "html", "explain"])]
+    since: Option<PathBuf>,
+
+    /// Print every path `--yes` would remove, one per line, instead of cleaning
+    #[arg(long, conflicts_with_all = ["free", "summary", "yes", "undo_last", "html", "explain", "since"])]
+    print_paths: bool,
+
+    /// Open the dashboard (true) or print the summary (false). Defaults to whether stderr is a terminal
+    #[arg(long, value_name = "BOOL")]
+    interactive: Option<bool>,
+
+    /// Auto-select low-risk categories until SIZE (e.g. 10GB) can be freed
+    #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+    free: Option<u64>,
+
+    /// Scan a preset set of categories
+    #[arg(long, value_enum, conflicts_with_all = ["only", "exclude"])]
+    profile: Option<Profile>,
+
+    /// Only scan these categories (comma-separated, e.g. node-modules,docker-images)
+    #[arg(long, value_name = "CATEGORIES", value_delimiter = ',', value_parser = parse_category, conflicts_with = "exclude")]
+    only: Vec<CategoryType>,
+
+    /// Skip these categories (comma-separated, e.g. downloads,trash)
+    #[arg(long, value_name = "CATEGORIES", value_delimiter = ',', value_parser = parse_category)]
+    exclude: Vec<CategoryType>,
+
+    /// Log more details (repeat for more: --verbose --verbose)
+    #[arg(long, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Write logs to this file (required to see logs in the dashboard)
+    #[arg(long, value_name = "PATH")]
+    log_file: Option<PathBuf>,
+
+    /// Read config.toml, the allowlist and sukkiri's state from DIR
+    /// (overrides `$SUKKIRI_CONFIG_DIR`)
+    #[arg(long, value_name = "DIR")]
+    config_dir: Option<PathBuf>,
+
+    /// Move the files of the last clean back out of the Trash
+    #[arg(long, conflicts_with = "free")]
+    undo_last: bool,
+
+    /// Clean without asking for confirmation (with --free, or every scanned category)
+    #[arg(short = 'y', long)]
+    yes: bool,
+
+    /// With --yes (or --print-paths), also clean (or list) high-risk categories such as Downloads
+    #[arg(long, requires = "unattended")]
+    force: bool,
+
+    /// Clean the newline-separated paths read from stdin instead of scanning
+    #[arg(long, requires = "yes", conflicts_with_all = ["free", "summary", "undo_last"])]
+    clean_stdin: bool,
+
+    /// Move cleaned items into DIR, keeping their path below it, instead of
+    /// trashing or deleting them (overrides `move_to` in config.toml)
+    #[arg(long, value_name = "DIR")]
+    move_to: Option<PathBuf>,
+
+    /// With --yes, print what would be cleaned without touching anything
+    #[arg(long, requires = "yes")]
+    dry_run: bool,
+
+    /// Re-scan every SECONDS while the dashboard is idle
+    #[arg(long, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+    watch: Option<u64>,
+
+    /// Scan with at most N threads (default: one per core)
+    #[arg(short = 'j', long, value_name = "N")]
+    jobs: Option<NonZeroUsize>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Scan and print a report without any way to clean
+    Audit {
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// Runs the `sukkiri` command line: the dashboard, or a headless scan, report or clean.
+///
+/// # Errors
+///
+/// Fails on an invalid `config.toml`, when the terminal can't be set up, when a
+/// scan is interrupted, or when a clean, undo or report can't be completed.
+pub fn run() -> Result<()> {
+    let cli = Cli::parse();
+    if let Some(dir) = &cli.config_dir {
+        config::set_config_dir(dir.clone());
+    }
+    let mut config = Config::load()?;
+    let home = home::require_home(home::resolve_home())?;
+    config.move_to = cli
+        .move_to
+        .clone()
+        .or(config.move_to)
+        .map(|dir| config::expand_home(&dir, &home));
+    let scan_options = scan_options(&cli, &config, &home);
+    scanner::thread_pool_builder(scan_options.jobs).build_global()?;
+
+    let interactive = cli.interactive.unwrap_or_else(|| {
+        should_run_interactive(io::stderr().is_terminal(), std::env::var_os("NO_COLOR"))
+    });
+    logging::init(cli.verbose, cli.log_file.as_deref(), interactive)?;
+    let sudo_warning = home::sudo_user().map(|user| home::sudo_warning(&user));
+    match &sudo_warning {
+        Some(warning) if interactive => log::warn!("{warning}"),
+        Some(warning) => eprintln!("{warning}"),
+        None => {}
+    }
+
+    let units = cli.units.unwrap_or(config.units);
+    interrupt::install(Arc::clone(&scan_options.cancelled));
+
+    if let Some(path) = &cli.explain {
+        print_explanation(path, &scan_options);
+        return Ok(());
+    }
+
+    // Audits return before any clean, undo or dashboard code is reachable
+    if let Some(Command::Audit { json }) = cli.command {
+        let report = audit::run(&scan_options, json, units)?;
+        ensure_complete()?;
+        print!("{report}");
+        return Ok(());
+    }
+
+    if cli.undo_last {
+        let restored = undo::undo_last_clean()?;
+        println!("Restored {restored} items from the Trash.");
+        return Ok(());
+    }
+
+    if cli.clean_stdin {
+        let items = scanner::paths::read_path_list(io::stdin().lock(), &home, &scan_options);
+        let plan = CleanPlan::from_items(&items, config.delete_mode)
+            .with_destination(config.move_to.clone());
+        return run_plan(plan, units, cli.dry_run);
+    }
+
+    if let Some(target) = cli.free
+        && (cli.yes || !interactive)
+    {
+        return free_space(target, cli.yes, cli.dry_run, &config, units, scan_options);
+    }
+
+    if cli.yes {
+        let max_risk = unattended_max_risk(cli.force);
+        return clean_unattended(max_risk, cli.dry_run, &config, units, scan_options);
+    }
+
+    if let Some(path) = cli.html {
+        return write_html(&path, &home, &scan_options, units);
+    }
+
+    if let Some(path) = cli.since {
+        let before = report::parse_json_summary(&fs::read_to_string(&path)?)?;
+        let results = scanner::scan_home(&home, &scan_options)?;
+        ensure_complete()?;
+        print!("{}", diff::since_report(&before, &results, units));
+        return Ok(());
+    }
+
+    if cli.print_paths {
+        let max_risk = unattended_max_risk(cli.force);
+        return print_paths(max_risk, &config, units, scan_options);
+    }
+
+    if cli.summary || !interactive {
+        let results = scanner::scan_home(&home, &scan_options)?;
+        ensure_complete()?;
+        print!("{}", report::text_summary(&results, units));
+        return Ok(());
+    }
+
+    let mut app = App::new_scanning(scan_options);
+    app.theme = Theme::from_config(&config.theme, cli.theme);
+    app.auto_select_target = cli.free;
+    app.delete_mode = config.delete_mode;
+    app.move_to.clone_from(&config.move_to);
+    app.units = units;
+    app.find_duplicates = cli.duplicates;
+    app.keep_latest_derived_data = config.xcode.keep_latest_derived_data;
+    app.keep_recent = config.keep_recent();
+    app.downloads_min_age =
+        Duration::from_secs(config.downloads.min_age_days * constants::SECS_PER_DAY);
+    app.watch_interval = cli.watch.map(Duration::from_secs);
+    app.pinned = pins::load();
+    app.message = sudo_warning;
+    app.start_scan();
+    run_dashboard(&mut app)
+}
+
+/// Shows the dashboard for `app` until it quits, restoring the terminal afterwards.
+fn run_dashboard(app: &mut App) -> Result<()> {
+    enable_raw_mode()?;
+    let mut stderr = io::stderr();
+    execute!(stderr, EnterAlternateScreen, EnableMouseCapture)?;
+    let backend = CrosstermBackend::new(stderr);
+    let mut terminal = Terminal::new(backend)?;
+
+    let res = ui::run_app(&mut terminal, app);
+
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+    terminal.show_cursor()?;
+
+    if let Err(err) = res {
+        println!("{err:?}");
+    }
+
+    Ok(())
+}
+
+/// Builds the scan settings from the command line, falling back to `config`.
+fn scan_options(cli: &Cli, config: &Config, home: &Path) -> ScanOptions {
+    ScanOptions {
+        allowlist: Allowlist::load(),
+        one_file_system: cli.one_file_system,
+        project_roots: if cli.projects_roots.is_empty() {
+            scanner::dev::discover_project_roots(home, &config.projects.roots(home))
+        } else {
+            cli.projects_roots.clone()
+        },
+        max_depth: cli.max_depth.or(config.projects.max_depth),
+        artifact_dirs: config.projects.artifact_dirs.clone(),
+        categories: cli.profile.map_or_else(
+            || category_filter(&cli.only, &cli.exclude),
+            Profile::categories,
+        ),
+        time_budget: (cli.time_budget > 0).then(|| Duration::from_secs(cli.time_budget)),
+        no_external_commands: cli.no_external_commands || config.no_external_commands,
+        jobs: cli.jobs.map(NonZeroUsize::get).or(config.jobs),
+        skip_open_files: cli.skip_open_files,
+        min_size: cli.min_size.unwrap_or(0),
+        min_age: cli
+            .min_age
+            .map(|days| Duration::from_secs(days * constants::SECS_PER_DAY)),
+        flatten_downloads: config.downloads.flatten_files,
+        screenshot_location: config
+            .screenshots
+            .location
+            .as_deref()
+            .map(|location| config::expand_home(location, home)),
+        ..ScanOptions::default()
+    }
+}
+
+/// Decides whether to launch the TUI dashboard.
+/// Following the `NO_COLOR` convention, any non-empty value disables it,
+/// as does running without a terminal (piped output, CI).
+fn should_run_interactive(is_terminal: bool, no_color: Option<OsString>) -> bool {
+    is_terminal && no_color.is_none_or(|value| value.is_empty())
+}
+
+/// Scans, auto-selects categories to free `target` bytes and prints the plan.
+/// The plan is only carried out when `yes` is given.
+fn free_space(
+    target: u64,
+    yes: bool,
+    dry_run: bool,
+    config: &Config,
+    units: Units,
+    scan_options: ScanOptions,
+) -> Result<()> {
+    let mut app = scan_headless(config, units, scan_options)?;
+    let reached = app.auto_select(target);
+
+    println!("Plan to free {}:", units.format(target));
+    let selected: Vec<ScanResult> = app
+        .results
+        .iter()
+        .filter(|r| r.is_selected)
+        .cloned()
+        .collect();
+    print!("{}", report::text_summary(&selected, units));
+    if !reached {
+        println!(
+            "Only {} can be freed from low-risk categories.",
+            units.format(app.total_selected_size())
+        );
+    }
+
+    if !yes {
+        println!("Run again with --yes to clean these categories.");
+        return Ok(());
+    }
+
+    clean_now(&app, config.delete_mode, units, dry_run)
+}
+
+/// The riskiest category cleaned without confirmation. High-risk categories
+/// such as Downloads need `--force`, whichever profile is used.
+fn unattended_max_risk(force: bool) -> Risk {
+    if force { Risk::High } else { Risk::Medium }
+}
+
+/// Cleans every scanned category without asking (`--yes` without `--free`).
+/// Categories riskier than `max_risk` are refused.
+fn clean_unattended(
+    max_risk: Risk,
+    dry_run: bool,
+    config: &Config,
+    units: Units,
+    scan_options: ScanOptions,
+) -> Result<()> {
+    let mut app = scan_headless(config, units, scan_options)?;
+    for category in app.select_unattended(max_risk) {
+        println!(
+            "Refusing to clean {} without confirmation (high risk). Add --force to clean it.",
+            category.name()
+        );
+    }
+    let selected: Vec<ScanResult> = app
+        .results
+        .iter()
+        .filter(|r| r.is_selected)
+        .cloned()
+        .collect();
+    print!("{}", report::text_summary(&selected, units));
+    clean_now(&app, config.delete_mode, units, dry_run)
+}
+
+/// Prints every path `--yes` would remove (`--print-paths`). Categories riskier
+/// than `max_risk` are left out, saying so on stderr to keep stdout pipeable.
+fn print_paths(
+    max_risk: Risk,
+    config: &Config,
+    units: Units,
+    scan_options: ScanOptions,
+) -> Result<()> {
+    let mut app = scan_headless(config, units, scan_options)?;
+    for category in app.select_unattended(max_risk) {
+        eprintln!(
+            "Leaving out {} (high risk). Add --force to list it.",
+            category.name()
+        );
+    }
+    let items: Vec<ScannedItem> = app
+        .selected_batches()
+        .into_iter()
+        .flat_map(|(_, items)| items)
+        .collect();
+    print!("{}", report::path_list(&items));
+    Ok(())
+}
+
+/// Scans and writes the HTML report to `path` (`--html`).
+fn write_html(path: &Path, home: &Path, scan_options: &ScanOptions, units: Units) -> Result<()> {
+    let results = scanner::scan_home(home, scan_options)?;
+    ensure_complete()?;
+    fs::write(path, report::html_report(&results, units))?;
+    println!("Wrote the report to {}", path.display());
+    Ok(())
+}
+
+/// Scans without the dashboard, into an app set up from `config`.
+fn scan_headless(config: &Config, units: Units, scan_options: ScanOptions) -> Result<App> {
+    let mut app = App::new_scanning(scan_options);
+    app.units = units;
+    app.downloads_min_age =
+        Duration::from_secs(config.downloads.min_age_days * constants::SECS_PER_DAY);
+    app.keep_latest_derived_data = config.xcode.keep_latest_derived_data;
+    app.keep_recent = config.keep_recent();
+    app.move_to.clone_from(&config.move_to);
+    app.results = scanner::scan_all(&app.scan_options)?;
+    ensure_complete()?;
+    Ok(app)
+}
+
+/// Fails if Ctrl-C cut the scan short, so partial results aren't printed
+/// (or cleaned) as if they were complete.
+fn ensure_complete() -> Result<()> {
+    if interrupt::is_interrupted() {
+        anyhow::bail!("Interrupted while scanning, nothing was reported or cleaned.");
+    }
+    Ok(())
+}
+
+/// Cleans the selected categories and prints how it went.
+fn clean_now(app: &App, delete_mode: DeleteMethod, units: Units, dry_run: bool) -> Result<()> {
+    let plan =
+        CleanPlan::new(&app.selected_batches(), delete_mode).with_destination(app.move_to.clone());
+    run_plan(plan, units, dry_run)
+}
+
+/// Carries out `plan` and prints how it went, or with `dry_run` only what it would do.
+/// Ctrl-C stops the clean after the item at hand.
+fn run_plan(plan: CleanPlan, units: Units, dry_run: bool) -> Result<()> {
+    if dry_run {
+        print!("{}", plan.describe(units));
+        println!("Dry run: nothing was cleaned.");
+        return Ok(());
+    }
+    let plan = plan.with_stop(interrupt::flag()).with_units(units);
+    let report = plan.execute(false, None)?;
+    let items: Vec<ScannedItem> = plan.items().cloned().collect();
+    print_clean_outcome(report.freed(&items), report, units);
+    Ok(())
+}
+
+/// Records the clean for `--undo-last` and prints what was freed and what was left behind.
+fn print_clean_outcome(freed: u64, report: CleanReport, units: Units) {
+    println!("Successfully cleaned {}!", units.format(freed));
+    let lifetime = stats::record_freed(freed).reclaimed;
+    println!("Lifetime reclaimed: {}", units.format(lifetime));
+    if !report.denied.is_empty() {
+        println!(
+            "{} items need administrator rights. Run again with sudo to remove them.",
+            report.denied.len()
+        );
+    }
+    if !report.in_use.is_empty() {
+        println!(
+            "{} items are in use by running apps. Quit them and run again to remove them.",
+            report.in_use.len()
+        );
+    }
+    if !report.skipped.is_empty() {
+        println!(
+            "Interrupted: {} items were left untouched.",
+            report.skipped.len()
+        );
+    }
+    undo::record_last_clean(report.trashed);
+}
+
+/// Prints the reasons `path` would be left out of the scan (`--explain`).
+fn print_explanation(path: &Path, options: &ScanOptions) {
+    let reasons = explain::explain(path, options);
+    if reasons.is_empty() {
+        println!("Nothing keeps {} out of the scan.", path.display());
+        return;
+    }
+    println!("{} is left out of the scan because:", path.display());
+    for reason in reasons {
+        println!("  - {reason}");
+    }
+}
+
+fn parse_category(slug: &str) -> Result<CategoryType, String> {
+    CategoryType::from_slug(slug).ok_or_else(|| format!("unknown category `{slug}`"))
+}
+
+/// Builds the category filter from `--only` / `--exclude` (which clap keeps exclusive).
+fn category_filter(only: &[CategoryType], exclude: &[CategoryType]) -> CategoryFilter {
+    if !only.is_empty() {
+        CategoryFilter::Only(only.iter().copied().collect())
+    } else if !exclude.is_empty() {
+        CategoryFilter::Exclude(exclude.iter().copied().collect())
+    } else {
+        CategoryFilter::All
+    }
+}
+
+/// Parses a size such as `10GB`, `1.5G`, `500MB` or `1024` (bytes).
+/// Units are binary, matching how sizes are displayed.
+fn parse_size(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("invalid size `{value}`"))?;
+
+    let multiplier: u64 = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => 1 << 10,
+        "M" | "MB" | "MIB" => 1 << 20,
+        "G" | "GB" | "GIB" => 1 << 30,
+        "T" | "TB" | "TIB" => 1 << 40,
+        _ => return Err(format!("unknown size unit in `{value}`")),
+    };
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    Ok((number * multiplier as f64) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn interactive_decision() {
+        assert!(should_run_interactive(true, None));
+        assert!(should_run_interactive(true, Some(OsString::new())));
+        assert!(!should_run_interactive(true, Some(OsString::from("1"))));
+        assert!(!should_run_interactive(false, None));
+        assert!(!should_run_interactive(false, Some(OsString::from("1"))));
+    }
+
+    #[test]
+    fn interactive_flag_takes_a_bool() {
+        let cli = Cli::try_parse_from(["sukkiri", "--interactive=false"]).unwrap();
+        assert_eq!(cli.interactive, Some(false));
+        assert!(Cli::try_parse_from(["sukkiri", "--interactive=maybe"]).is_err());
+    }
+
+    #[test]
+    fn parse_category_lists() {
+        let cli = Cli::try_parse_from(["sukkiri", "--only", "node-modules,docker-images"]).unwrap();
+        assert_eq!(
+            cli.only,
+            vec![CategoryType::NodeModules, CategoryType::DockerImages]
+        );
+        assert_eq!(
+            category_filter(&cli.only, &cli.exclude),
+            CategoryFilter::Only(HashSet::from([
+                CategoryType::NodeModules,
+                CategoryType::DockerImages
+            ]))
+        );
+
+        let cli = Cli::try_parse_from(["sukkiri", "--exclude", "downloads"]).unwrap();
+        assert_eq!(
+            category_filter(&cli.only, &cli.exclude),
+            CategoryFilter::Exclude(HashSet::from([CategoryType::Downloads]))
+        );
+
+        let cli = Cli::try_parse_from(["sukkiri"]).unwrap();
+        assert_eq!(
+            category_filter(&cli.only, &cli.exclude),
+            CategoryFilter::All
+        );
+
+        assert!(Cli::try_parse_from(["sukkiri", "--only", "bogus"]).is_err());
+    }
+
+    #[test]
+    fn only_and_exclude_are_exclusive() {
+        let err = Cli::try_parse_from(["sukkiri", "--only", "trash", "--exclude", "downloads"])
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn profile_replaces_only_and_exclude() {
+        let cli = Cli::try_parse_from(["sukkiri", "--profile", "developer"]).unwrap();
+        assert_eq!(cli.profile, Some(Profile::Developer));
+        let err = Cli::try_parse_from(["sukkiri", "--profile", "minimal", "--only", "trash"])
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn force_requires_yes() {
+        assert!(Cli::try_parse_from(["sukkiri", "--yes", "--force"]).is_ok());
+        let err = Cli::try_parse_from(["sukkiri", "--force"]).err().unwrap();
+        assert_eq!(err.kind(), clap::error::ErrorKind::MissingRequiredArgument);
+        assert!(Cli::try_parse_from(["sukkiri", "--print-paths", "--force"]).is_ok());
+    }
+
+    #[test]
+    fn dry_run_requires_yes() {
+        assert!(Cli::try_parse_from(["sukkiri", "--yes", "--dry-run"]).is_ok());
+        assert!(Cli::try_parse_from(["sukkiri", "--dry-run"]).is_err());
+    }
+
+    #[test]
+    fn summary_never_cleans() {
+        assert!(Cli::try_parse_from(["sukkiri", "--summary"]).is_ok());
+        assert!(Cli::try_parse_from(["sukkiri", "--summary", "--yes"]).is_err());
+    }
+
+    #[test]
+    fn clean_stdin_requires_yes() {
+        assert!(Cli::try_parse_from(["sukkiri", "--clean-stdin", "--yes"]).is_ok());
+        let err = Cli::try_parse_from(["sukkiri", "--clean-stdin"])
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), clap::error::ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn parse_audit_subcommand() {
+        let cli = Cli::try_parse_from(["sukkiri", "--only", "trash", "audit", "--json"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Audit { json: true })));
+        assert_eq!(cli.only, vec![CategoryType::Trash]);
+    }
+
+    #[test]
+    fn min_size_and_age_reach_the_scan() {
+        let home = Path::new("/Users/test");
+        let cli = Cli::try_parse_from(["sukkiri", "--min-size", "1MB", "--min-age", "7"]).unwrap();
+        let options = scan_options(&cli, &Config::default(), home);
+        assert_eq!(options.min_size, 1 << 20);
+        assert_eq!(options.min_age, Some(Duration::from_hours(7 * 24)));
+
+        let cli = Cli::try_parse_from(["sukkiri"]).unwrap();
+        let options = scan_options(&cli, &Config::default(), home);
+        assert_eq!(options.min_size, 0);
+        assert_eq!(options.min_age, None);
+    }
+
+    #[test]
+    fn parse_size_units() {
+        assert_eq!(parse_size("1024"), Ok(1024));
+        assert_eq!(parse_size("10GB"), Ok(10 << 30));
+        assert_eq!(parse_size("1.5g"), Ok(3 << 29));
+        assert_eq!(parse_size("500 MiB"), Ok(500 << 20));
+        assert!(parse_size("ten GB").is_err());
+        assert!(parse_size("10PB").is_err());
+    }
+}
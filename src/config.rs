@@ -0,0 +1,335 @@
+use crate::cleaner::DeleteMethod;
+use crate::constants::DOWNLOADS_MIN_AGE_DAYS;
+use crate::model::CategoryType;
+use crate::ui::theme::ThemePreset;
+use crate::units::Units;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Points sukkiri at another configuration directory, e.g. kept with dotfiles.
+pub const CONFIG_DIR_ENV: &str = "SUKKIRI_CONFIG_DIR";
+
+/// Set by `--config-dir`, which takes precedence over the environment.
+static CONFIG_DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Makes [`config_dir`] return `dir` from now on. Only the first call has an effect.
+pub fn set_config_dir(dir: PathBuf) {
+    let _ = CONFIG_DIR_OVERRIDE.set(dir);
+}
+
+/// Returns the directory holding sukkiri's configuration files:
+/// `--config-dir`, else `$SUKKIRI_CONFIG_DIR`, else `sukkiri` in the system's configuration directory.
+pub fn config_dir() -> Option<PathBuf> {
+    resolve_config_dir(
+        CONFIG_DIR_OVERRIDE.get().cloned(),
+        std::env::var_os(CONFIG_DIR_ENV),
+        dirs::config_dir(),
+    )
+}
+
+fn resolve_config_dir(
+    configured: Option<PathBuf>,
+    env: Option<OsString>,
+    system: Option<PathBuf>,
+) -> Option<PathBuf> {
+    configured
+        .or_else(|| env.filter(|dir| !dir.is_empty()).map(PathBuf::from))
+        .or_else(|| system.map(|dir| dir.join("sukkiri")))
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Default delete mode for the dashboard and `--free --yes`.
+    pub delete_mode: DeleteMethod,
+    /// Move cleaned items into this folder instead (`--move-to`). A leading `~` is the home folder.
+    pub move_to: Option<PathBuf>,
+    pub units: Units,
+    /// Never run external commands such as `docker` while scanning.
+    pub no_external_commands: bool,
+    /// How many threads to scan with. Defaults to one per core.
+    pub jobs: Option<usize>,
+    pub theme: ThemeConfig,
+    pub projects: ProjectsConfig,
+    pub downloads: DownloadsConfig,
+    pub xcode: XcodeConfig,
+    pub screenshots: ScreenshotsConfig,
+}
+
+/// The `[theme]` section: a preset plus optional per-color overrides.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    pub preset: Option<ThemePreset>,
+    pub primary: Option<String>,
+    pub secondary: Option<String>,
+    pub accent: Option<String>,
+    pub border: Option<String>,
+    pub muted: Option<String>,
+    pub success: Option<String>,
+    pub warning: Option<String>,
+}
+
+/// The `[projects]` section: where to look for `node_modules` and build artifacts.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ProjectsConfig {
+    pub roots: Vec<PathBuf>,
+    pub max_depth: Option<usize>,
+    pub artifact_dirs: Option<Vec<String>>,
+}
+
+/// The `[downloads]` section: how much of Downloads gets selected.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct DownloadsConfig {
+    /// Only files older than this are selected when Downloads is toggled on.
+    pub min_age_days: u64,
+    /// List every file inside Downloads folders as its own item.
+    pub flatten_files: bool,
+    /// Leave this many of the newest downloads unselected.
+    pub keep_recent: usize,
+}
+
+impl Default for DownloadsConfig {
+    fn default() -> Self {
+        Self {
+            min_age_days: DOWNLOADS_MIN_AGE_DAYS,
+            flatten_files: false,
+            keep_recent: 0,
+        }
+    }
+}
+
+/// The `[xcode]` section: how much of the Xcode data gets selected.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct XcodeConfig {
+    /// Leave each project's most recently built `DerivedData` folder unselected.
+    pub keep_latest_derived_data: bool,
+}
+
+impl Default for XcodeConfig {
+    fn default() -> Self {
+        Self {
+            keep_latest_derived_data: true,
+        }
+    }
+}
+
+/// The `[screenshots]` section: where screenshots and screen recordings are saved,
+/// and how many of them to keep.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ScreenshotsConfig {
+    /// Overrides the location set in the Screenshot app (`com.apple.screencapture`).
+    pub location: Option<PathBuf>,
+    /// Leave this many of the newest screenshots and recordings unselected.
+    pub keep_recent: usize,
+}
+
+impl ProjectsConfig {
+    /// Returns the configured roots with a leading `~` expanded to `home`.
+    pub fn roots(&self, home: &Path) -> Vec<PathBuf> {
+        self.roots
+            .iter()
+            .map(|root| expand_home(root, home))
+            .collect()
+    }
+}
+
+/// Expands a leading `~` in a configured path to the home directory.
+pub fn expand_home(path: &Path, home: &Path) -> PathBuf {
+    match path.strip_prefix("~") {
+        Ok(rest) => home.join(rest),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+impl Config {
+    /// How many of the newest items each category leaves unselected, for the
+    /// categories that keep any.
+    pub fn keep_recent(&self) -> HashMap<CategoryType, usize> {
+        [
+            (CategoryType::Downloads, self.downloads.keep_recent),
+            (CategoryType::ScreenCapture, self.screenshots.keep_recent),
+        ]
+        .into_iter()
+        .filter(|&(_, count)| count > 0)
+        .collect()
+    }
+
+    /// Loads the configuration from `config.toml` in the configuration directory.
+    /// Returns the default configuration if the file doesn't exist.
+    pub fn load() -> anyhow::Result<Self> {
+        config_dir().map_or_else(
+            || Ok(Self::default()),
+            |dir| Self::load_from(&dir.join("config.toml")),
+        )
+    }
+
+    /// Reads the configuration at `path`, failing on a file that can't be read or
+    /// parsed rather than quietly cleaning with the defaults.
+    fn load_from(path: &Path) -> anyhow::Result<Self> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(anyhow::anyhow!("Could not read {}: {e}", path.display())),
+        };
+        Self::parse(&content).map_err(|e| anyhow::anyhow!("Invalid {}: {e}", path.display()))
+    }
+
+    pub fn parse(content: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn malformed_config_is_an_error() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("config.toml");
+        assert_eq!(Config::load_from(&path)?.delete_mode, DeleteMethod::Trash);
+
+        fs::write(&path, "delete_mode = \"shred\"\n")?;
+        let err = Config::load_from(&path).unwrap_err().to_string();
+        assert!(err.starts_with("Invalid "), "{err}");
+        assert!(err.contains("config.toml"), "{err}");
+        Ok(())
+    }
+
+    #[test]
+    fn parse_theme_section() {
+        let config = Config::parse(
+            r##"
+[theme]
+preset = "high-contrast"
+primary = "#ff8800"
+"##,
+        )
+        .unwrap();
+
+        assert_eq!(config.theme.preset, Some(ThemePreset::HighContrast));
+        assert_eq!(config.theme.primary.as_deref(), Some("#ff8800"));
+        assert!(config.theme.accent.is_none());
+    }
+
+    #[test]
+    fn parse_projects_section() {
+        let config = Config::parse(
+            r#"
+[projects]
+roots = ["~/dev", "/opt/src"]
+max_depth = 8
+artifact_dirs = ["target", ".next"]
+"#,
+        )
+        .unwrap();
+
+        let home = Path::new("/Users/test");
+        assert_eq!(
+            config.projects.roots(home),
+            vec![PathBuf::from("/Users/test/dev"), PathBuf::from("/opt/src")]
+        );
+        assert_eq!(config.projects.max_depth, Some(8));
+        assert_eq!(
+            config.projects.artifact_dirs,
+            Some(vec!["target".to_string(), ".next".to_string()])
+        );
+    }
+
+    #[test]
+    fn expand_home_only_replaces_leading_tilde() {
+        let home = Path::new("/Users/test");
+        assert_eq!(expand_home(Path::new("~"), home), home);
+        assert_eq!(
+            expand_home(Path::new("/data/~backup"), home),
+            PathBuf::from("/data/~backup")
+        );
+    }
+
+    #[test]
+    fn parse_empty_config() {
+        let config = Config::parse("").unwrap();
+        assert!(config.theme.preset.is_none());
+        assert_eq!(config.delete_mode, DeleteMethod::Trash);
+        assert_eq!(config.units, Units::Binary);
+        assert!(!config.no_external_commands);
+        assert!(config.xcode.keep_latest_derived_data);
+        assert_eq!(config.downloads.min_age_days, DOWNLOADS_MIN_AGE_DAYS);
+        assert!(!config.downloads.flatten_files);
+    }
+
+    #[test]
+    fn parse_xcode_section() {
+        let config = Config::parse("[xcode]\nkeep_latest_derived_data = false").unwrap();
+        assert!(!config.xcode.keep_latest_derived_data);
+    }
+
+    #[test]
+    fn parse_screenshots_section() {
+        let config = Config::parse("[screenshots]\nlocation = \"~/Pictures/Screenshots\"").unwrap();
+        assert_eq!(
+            config.screenshots.location,
+            Some(PathBuf::from("~/Pictures/Screenshots"))
+        );
+        assert!(Config::parse("").unwrap().screenshots.location.is_none());
+
+        let config = Config::parse("[screenshots]\nkeep_recent = 20").unwrap();
+        assert_eq!(
+            config.keep_recent(),
+            HashMap::from([(CategoryType::ScreenCapture, 20)])
+        );
+        assert!(Config::parse("").unwrap().keep_recent().is_empty());
+    }
+
+    #[test]
+    fn config_dir_overrides_take_precedence() {
+        let system = Some(PathBuf::from("/Users/test/Library/Application Support"));
+        assert_eq!(
+            resolve_config_dir(None, None, system.clone()),
+            Some(PathBuf::from(
+                "/Users/test/Library/Application Support/sukkiri"
+            ))
+        );
+        assert_eq!(
+            resolve_config_dir(None, Some(OsString::new()), system.clone()),
+            Some(PathBuf::from(
+                "/Users/test/Library/Application Support/sukkiri"
+            ))
+        );
+        assert_eq!(
+            resolve_config_dir(None, Some("/tmp/env".into()), system.clone()),
+            Some(PathBuf::from("/tmp/env"))
+        );
+        assert_eq!(
+            resolve_config_dir(Some("/tmp/flag".into()), Some("/tmp/env".into()), system),
+            Some(PathBuf::from("/tmp/flag"))
+        );
+    }
+
+    #[test]
+    fn parse_units() {
+        let config = Config::parse(r#"units = "decimal""#).unwrap();
+        assert_eq!(config.units, Units::Decimal);
+    }
+
+    #[test]
+    fn parse_delete_mode() {
+        let config = Config::parse(r#"delete_mode = "permanent""#).unwrap();
+        assert_eq!(config.delete_mode, DeleteMethod::Permanent);
+        assert!(Config::parse(r#"delete_mode = "shred""#).is_err());
+        assert_eq!(config.move_to, None);
+
+        let config = Config::parse(r#"move_to = "~/Review""#).unwrap();
+        assert_eq!(config.move_to, Some(PathBuf::from("~/Review")));
+    }
+}
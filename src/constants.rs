@@ -1,22 +1,59 @@
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Printed by `--version`: the version and the target triple it was built for.
+pub const VERSION_INFO: &str = concat!(env!("CARGO_PKG_VERSION"), " (", env!("TARGET"), ")");
+
 pub const TRASH_DIR: &str = ".Trash";
 pub const LIBRARY_LOGS: &str = "Library/Logs";
 pub const LIBRARY_CACHES: &str = "Library/Caches";
 pub const VAR_LOG: &str = "/private/var/log";
 pub const SYSTEM_LIBRARY_LOGS: &str = "/Library/Logs";
 pub const SYSTEM_LIBRARY_CACHES: &str = "/Library/Caches";
+/// Root-owned locations that can't be cleaned without `sudo`.
+pub const PRIVILEGED_DIRS: &[&str] = &["/Library", "/System", "/private/var/log", "/var/log"];
 
 pub const GOOGLE_CHROME_CACHE: &str = "Library/Caches/Google/Chrome";
 pub const SAFARI_CACHE: &str = "Library/Caches/com.apple.Safari";
 pub const FIREFOX_CACHE: &str = "Library/Caches/Firefox";
 
+pub const QUICKLOOK_THUMBNAIL_CACHE: &str = "com.apple.QuickLook.thumbnailcache";
+
 pub const DOWNLOADS_DIR: &str = "Downloads";
+pub const DOWNLOADS_MIN_AGE_DAYS: u64 = 30;
+/// Extensions of the installers and archives that pile up in Downloads.
+pub const INSTALLER_EXTENSIONS: &[&str] = &["dmg", "pkg", "mpkg", "zip", "xip", "iso"];
+/// Extensions of folders that macOS shows as a single app, plug-in or framework.
+pub const BUNDLE_EXTENSIONS: &[&str] = &["app", "framework", "bundle", "plugin", "appex", "kext"];
+pub const SECS_PER_DAY: u64 = 24 * 60 * 60;
 pub const DESKTOP_DIR: &str = "Desktop";
-pub const PROJECTS_DIR: &str = "Projects";
+/// How macOS names screenshots and screen recordings, in English and the common localizations.
+pub const SCREEN_CAPTURE_PREFIXES: &[&str] = &[
+    "Screenshot",
+    "Screen Shot",
+    "Screen Recording",
+    "スクリーンショット",
+    "画面収録",
+    "Bildschirmfoto",
+    "Bildschirmaufnahme",
+    "Capture d’écran",
+    "Enregistrement de l’écran",
+    "截屏",
+    "录屏",
+];
+/// Formats screenshots (`png` by default, or as set with `defaults write com.apple.screencapture type`)
+/// and screen recordings are saved in.
+pub const SCREEN_CAPTURE_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "heic", "tiff", "gif", "pdf", "mov", "mp4",
+];
+/// Common project roots below the home directory, searched when they exist.
+pub const PROJECTS_DIRS: &[&str] = &["Projects", "Developer", "dev", "src", "code", "work"];
 
 pub const XCODE_DERIVED_DATA: &str = "Library/Developer/Xcode/DerivedData";
 pub const XCODE_ARCHIVES: &str = "Library/Developer/Xcode/Archives";
 pub const XCODE_DEVICE_SUPPORT: &str = "Library/Developer/Xcode/iOS DeviceSupport";
-pub const CORE_SIMULATOR: &str = "Library/Developer/CoreSimulator";
+/// Simulator caches (`dyld` shared caches) and runtime images, safe to rebuild.
+/// `CoreSimulator/Devices` holds the simulators' state and is left alone.
+pub const CORE_SIMULATOR_CACHES: &str = "Library/Developer/CoreSimulator/Caches";
+pub const CORE_SIMULATOR_IMAGES: &str = "Library/Developer/CoreSimulator/Images";
 
 pub const NPM_CACHE: &str = ".npm";
 pub const BUN_CACHE: &str = ".bun/install/cache";
@@ -26,3 +63,27 @@ pub const CARGO_REGISTRY: &str = ".cargo/registry";
 pub const GRADLE_CACHE: &str = ".gradle/caches";
 
 pub const NODE_MODULES: &str = "node_modules";
+pub const PROJECTS_MAX_DEPTH: usize = 5;
+pub const SCAN_TIME_BUDGET_SECS: u64 = 120;
+pub const DUPLICATE_MIN_SIZE: u64 = 1 << 20;
+/// Below this share of free space the disk counts as critically full.
+pub const DISK_CRITICAL_FREE_RATIO: f64 = 0.10;
+/// How often the dashboard reads the free disk space again.
+pub const DISK_REFRESH_SECS: u64 = 5;
+/// How often each scanner passes its progress on to the dashboard.
+pub const PROGRESS_INTERVAL_MS: u64 = 100;
+/// How many of the largest categories are highlighted when the disk is critically full.
+pub const BIGGEST_WINS: usize = 3;
+/// How many of the largest items the HTML report lists.
+pub const HTML_TOP_ITEMS: usize = 20;
+/// Frames of the spinner shown next to categories that are still scanning.
+pub const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+/// Space `--move-to` leaves free on the destination volume, so moving can't fill it up.
+pub const MOVE_FREE_SPACE_RESERVE: u64 = 1 << 30;
+/// How many of the items that changed the most `--since` lists.
+pub const SINCE_TOP_ITEMS: usize = 20;
+/// Terminals narrower than this many columns show the details below the category list.
+pub const COMPACT_WIDTH: u16 = 100;
+/// Width in cells of the share-of-total bar in each category row.
+pub const SIZE_BAR_WIDTH: usize = 10;
+pub const BUILD_ARTIFACT_DIRS: &[&str] = &["target", ".next", "dist", "build", ".gradle", ".venv"];
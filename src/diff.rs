@@ -0,0 +1,290 @@
+use crate::constants::SINCE_TOP_ITEMS;
+use crate::model::ScanResult;
+use crate::report::{JsonCategory, JsonSummary};
+use crate::units::Units;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// A category or item whose size differs between two scans. Missing counts as 0 bytes.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Change<T> {
+    pub what: T,
+    pub before: u64,
+    pub after: u64,
+}
+
+impl<T> Change<T> {
+    fn magnitude(&self) -> u64 {
+        self.after.abs_diff(self.before)
+    }
+
+    /// The change with its sign, e.g. `+1.20 GiB`.
+    fn signed(&self, units: Units) -> String {
+        let sign = if self.after >= self.before { '+' } else { '-' };
+        format!("{sign}{}", units.format(self.magnitude()))
+    }
+}
+
+/// The saved categories that were scanned again, so ones left out of this scan
+/// (e.g. with `--only`) don't show up as emptied.
+fn rescanned<'a>(
+    before: &'a JsonSummary,
+    after: &[ScanResult],
+) -> impl Iterator<Item = &'a JsonCategory> {
+    let scanned: HashSet<&str> = after.iter().map(|r| r.category.slug()).collect();
+    before
+        .categories
+        .iter()
+        .filter(move |c| scanned.contains(c.category.as_str()))
+}
+
+/// Categories of this scan that grew or shrank since the saved report.
+pub fn category_changes(before: &JsonSummary, after: &[ScanResult]) -> Vec<Change<String>> {
+    let saved: HashMap<&str, u64> = rescanned(before, after)
+        .map(|c| (c.category.as_str(), c.size))
+        .collect();
+    let mut changes: Vec<Change<String>> = after
+        .iter()
+        .map(|r| Change {
+            what: r.category.name().to_string(),
+            before: saved.get(r.category.slug()).copied().unwrap_or(0),
+            after: r.total_size,
+        })
+        .collect();
+    changes.retain(|change| change.before != change.after);
+    changes
+}
+
+/// Items that appeared, disappeared or changed size, biggest change first.
+/// Categories saved without their items (older reports) or not scanned again are left out.
+pub fn item_changes(before: &JsonSummary, after: &[ScanResult]) -> Vec<Change<PathBuf>> {
+    let comparable: HashSet<&str> = rescanned(before, after)
+        .filter(|c| c.items == 0 || !c.entries.is_empty())
+        .map(|c| c.category.as_str())
+        .collect();
+    let mut sizes: HashMap<&Path, (u64, u64)> = HashMap::new();
+    for category in rescanned(before, after) {
+        for entry in &category.entries {
+            sizes.entry(&entry.path).or_default().0 += entry.size;
+        }
+    }
+    for result in after
+        .iter()
+        .filter(|r| comparable.contains(r.category.slug()))
+    {
+        for item in &result.items {
+            sizes.entry(&item.path).or_default().1 += item.size;
+        }
+    }
+    let mut changes: Vec<Change<PathBuf>> = sizes
+        .into_iter()
+        .filter(|(_, (before, after))| before != after)
+        .map(|(path, (before, after))| Change {
+            what: path.to_path_buf(),
+            before,
+            after,
+        })
+        .collect();
+    changes.sort_by(|a, b| {
+        b.magnitude()
+            .cmp(&a.magnitude())
+            .then_with(|| a.what.cmp(&b.what))
+    });
+    changes
+}
+
+/// Formats what grew and shrank since the saved report (`--since`), totalled
+/// over the categories of this scan.
+pub fn since_report(before: &JsonSummary, after: &[ScanResult], units: Units) -> String {
+    let categories = category_changes(before, after);
+    if categories.is_empty() {
+        return "Nothing changed since the saved report.\n".to_string();
+    }
+    let mut out = String::new();
+    for change in &categories {
+        let _ = writeln!(
+            out,
+            "{:<22} {:>12}  ({} → {})",
+            change.what,
+            change.signed(units),
+            units.format(change.before),
+            units.format(change.after)
+        );
+    }
+    let total = Change {
+        what: (),
+        before: rescanned(before, after).map(|c| c.size).sum(),
+        after: after.iter().map(|r| r.total_size).sum(),
+    };
+    let _ = writeln!(out, "{:<22} {:>12}", "Total", total.signed(units));
+
+    let items = item_changes(before, after);
+    if !items.is_empty() {
+        let _ = writeln!(out, "\nLargest changes:");
+    }
+    for change in items.iter().take(SINCE_TOP_ITEMS) {
+        let note = match (change.before, change.after) {
+            (0, _) => " (new)",
+            (_, 0) => " (gone)",
+            _ => "",
+        };
+        let _ = writeln!(
+            out,
+            "{:>12}  {}{note}",
+            change.signed(units),
+            change.what.display()
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{CategoryType, ScannedItem};
+    use crate::report::JsonItem;
+    use std::time::{Duration, SystemTime};
+
+    fn saved(category: CategoryType, entries: &[(&str, u64)]) -> JsonCategory {
+        JsonCategory {
+            category: category.slug().to_string(),
+            name: category.name().to_string(),
+            size: entries.iter().map(|(_, size)| size).sum(),
+            items: entries.len(),
+            root_path: PathBuf::from("/"),
+            timed_out: false,
+            entries: entries
+                .iter()
+                .map(|&(path, size)| JsonItem {
+                    path: PathBuf::from(path),
+                    size,
+                })
+                .collect(),
+        }
+    }
+
+    fn scanned(category: CategoryType, items: &[(&str, u64)]) -> ScanResult {
+        ScanResult {
+            category,
+            total_size: items.iter().map(|(_, size)| size).sum(),
+            items: items
+                .iter()
+                .map(|&(path, size)| ScannedItem {
+                    path: PathBuf::from(path),
+                    size,
+                    modified: SystemTime::UNIX_EPOCH,
+                })
+                .collect(),
+            is_selected: false,
+            root_path: PathBuf::from("/"),
+            roots: Vec::new(),
+            timed_out: false,
+            duration: Duration::ZERO,
+            offloaded: 0,
+            warnings: Vec::new(),
+            note: None,
+            in_use: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn reports_what_grew_and_shrank() {
+        let categories = vec![
+            saved(
+                CategoryType::UserCache,
+                &[("/c/app", 1024), ("/c/old", 512)],
+            ),
+            saved(CategoryType::Trash, &[("/t/junk", 2048)]),
+            saved(CategoryType::Downloads, &[("/d/same.dmg", 100)]),
+        ];
+        let before = JsonSummary {
+            total_size: categories.iter().map(|c| c.size).sum(),
+            total_items: 4,
+            categories,
+        };
+        let after = vec![
+            scanned(
+                CategoryType::UserCache,
+                &[("/c/app", 3072), ("/c/new", 1024)],
+            ),
+            scanned(CategoryType::Trash, &[]),
+            scanned(CategoryType::Downloads, &[("/d/same.dmg", 100)]),
+        ];
+
+        assert_eq!(
+            since_report(&before, &after, Units::Binary),
+            "\
+User Cache Files          +2.50 KiB  (1.50 KiB → 4 KiB)
+Trash                        -2 KiB  (2 KiB → 0 B)
+Total                        +512 B
+
+Largest changes:
+      +2 KiB  /c/app
+      -2 KiB  /t/junk (gone)
+      +1 KiB  /c/new (new)
+      -512 B  /c/old (gone)
+"
+        );
+        assert_eq!(
+            since_report(&before, &before_as_scanned(&before), Units::Binary),
+            "Nothing changed since the saved report.\n"
+        );
+    }
+
+    fn before_as_scanned(before: &JsonSummary) -> Vec<ScanResult> {
+        before
+            .categories
+            .iter()
+            .map(|c| {
+                let items: Vec<(&str, u64)> = c
+                    .entries
+                    .iter()
+                    .map(|e| (e.path.to_str().unwrap(), e.size))
+                    .collect();
+                scanned(CategoryType::from_slug(&c.category).unwrap(), &items)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn categories_left_out_of_the_scan_are_not_compared() {
+        let categories = vec![
+            saved(CategoryType::UserCache, &[("/c/app", 1024)]),
+            saved(CategoryType::Trash, &[("/t/junk", 2048)]),
+        ];
+        let before = JsonSummary {
+            total_size: 3072,
+            total_items: 2,
+            categories,
+        };
+        // As with `--only user-cache`
+        let after = vec![scanned(CategoryType::UserCache, &[("/c/app", 1536)])];
+
+        assert_eq!(
+            since_report(&before, &after, Units::Binary),
+            "\
+User Cache Files             +512 B  (1 KiB → 1.50 KiB)
+Total                        +512 B
+
+Largest changes:
+      +512 B  /c/app
+"
+        );
+    }
+
+    #[test]
+    fn reports_without_items_only_compare_categories() {
+        let mut cache = saved(CategoryType::UserCache, &[("/c/app", 1024)]);
+        cache.entries.clear();
+        let before = JsonSummary {
+            total_size: 1024,
+            total_items: 1,
+            categories: vec![cache],
+        };
+        let after = vec![scanned(CategoryType::UserCache, &[("/c/app", 2048)])];
+
+        assert_eq!(category_changes(&before, &after).len(), 1);
+        assert!(item_changes(&before, &after).is_empty());
+    }
+}
@@ -0,0 +1,107 @@
+use anyhow::{Result, anyhow};
+use std::fs;
+use std::path::PathBuf;
+
+const PASSWD: &str = "/etc/passwd";
+const USERS_DIR: &str = "/Users";
+
+/// Returns the home directory of the user running sukkiri.
+/// Under `sudo` this is the invoking user's home (from `SUDO_USER`),
+/// not root's, so `sudo sukkiri` still cleans the right account.
+pub fn resolve_home() -> Option<PathBuf> {
+    let sudo_user = std::env::var("SUDO_USER").ok();
+    let passwd = fs::read_to_string(PASSWD).unwrap_or_default();
+    resolve_home_from(sudo_user.as_deref(), &passwd, dirs::home_dir())
+}
+
+/// Like [`resolve_home`], but fails with a message telling the user what to fix.
+pub fn require_home(home: Option<PathBuf>) -> Result<PathBuf> {
+    home.ok_or_else(|| anyhow!("Could not determine home directory; set $HOME"))
+}
+
+/// Returns the user that invoked `sudo`, if any.
+pub fn sudo_user() -> Option<String> {
+    std::env::var("SUDO_USER")
+        .ok()
+        .filter(|user| !user.is_empty() && user != "root")
+}
+
+/// Explains what running as root under `sudo` means for `user`'s files.
+pub fn sudo_warning(user: &str) -> String {
+    format!(
+        "Running under sudo: cleaning {user}'s home as root. Items trashed from it will be \
+         owned by root, and undo history and stats are saved to root's settings."
+    )
+}
+
+fn resolve_home_from(
+    sudo_user: Option<&str>,
+    passwd: &str,
+    default: Option<PathBuf>,
+) -> Option<PathBuf> {
+    match sudo_user {
+        Some(user) if !user.is_empty() && user != "root" => {
+            // macOS keeps regular users in Directory Services rather than /etc/passwd
+            Some(
+                home_from_passwd(passwd, user)
+                    .unwrap_or_else(|| PathBuf::from(USERS_DIR).join(user)),
+            )
+        }
+        _ => default,
+    }
+}
+
+/// Looks up `user`'s home directory in passwd(5) formatted `content`.
+fn home_from_passwd(content: &str, user: &str) -> Option<PathBuf> {
+    content
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .map(|line| line.split(':').collect::<Vec<_>>())
+        .find(|fields| fields.len() >= 7 && fields[0] == user)
+        .map(|fields| PathBuf::from(fields[5]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSWD_FIXTURE: &str = "\
+# comment
+root:*:0:0:System Administrator:/var/root:/bin/sh
+alice:*:501:20:Alice:/home/alice:/bin/zsh
+";
+
+    #[test]
+    fn resolve_home_without_sudo_user() {
+        let default = Some(PathBuf::from("/var/root"));
+        assert_eq!(
+            resolve_home_from(None, PASSWD_FIXTURE, default.clone()),
+            default
+        );
+        assert_eq!(
+            resolve_home_from(Some("root"), PASSWD_FIXTURE, default.clone()),
+            default
+        );
+    }
+
+    #[test]
+    fn resolve_home_with_sudo_user() {
+        let default = Some(PathBuf::from("/var/root"));
+        assert_eq!(
+            resolve_home_from(Some("alice"), PASSWD_FIXTURE, default.clone()),
+            Some(PathBuf::from("/home/alice"))
+        );
+        // Not in passwd: fall back to the macOS user directory
+        assert_eq!(
+            resolve_home_from(Some("bob"), PASSWD_FIXTURE, default),
+            Some(PathBuf::from("/Users/bob"))
+        );
+    }
+
+    #[test]
+    fn sudo_warning_names_the_user() {
+        let warning = sudo_warning("alice");
+        assert!(warning.contains("alice's home"));
+        assert!(warning.contains("owned by root"));
+    }
+}
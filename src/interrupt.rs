@@ -0,0 +1,46 @@
+use log::{info, warn};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock};
+
+/// Set once Ctrl-C was pressed. Cleaning stops after the item at hand.
+static INTERRUPTED: LazyLock<Arc<AtomicBool>> = LazyLock::new(Arc::default);
+
+/// The flag set by Ctrl-C, for handing to a clean.
+pub fn flag() -> Arc<AtomicBool> {
+    Arc::clone(&INTERRUPTED)
+}
+
+/// Whether Ctrl-C was pressed.
+pub fn is_interrupted() -> bool {
+    INTERRUPTED.load(Ordering::Relaxed)
+}
+
+/// Handles SIGINT by setting the interrupt flag and `cancel_scan` instead of
+/// exiting, so no directory is left half-deleted and the terminal can be restored.
+pub fn install(cancel_scan: Arc<AtomicBool>) {
+    let flags = [flag(), cancel_scan];
+    if let Err(e) = ctrlc::set_handler(move || raise(&flags)) {
+        warn!("Failed to install the Ctrl-C handler: {e}");
+    }
+}
+
+fn raise(flags: &[Arc<AtomicBool>]) {
+    info!("Interrupted, stopping after the current item");
+    for flag in flags {
+        flag.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signal_sets_every_flag() {
+        let interrupted = Arc::new(AtomicBool::new(false));
+        let cancel_scan = Arc::new(AtomicBool::new(false));
+        raise(&[Arc::clone(&interrupted), Arc::clone(&cancel_scan)]);
+        assert!(interrupted.load(Ordering::Relaxed));
+        assert!(cancel_scan.load(Ordering::Relaxed));
+    }
+}
@@ -0,0 +1,42 @@
+//! A lightweight disk cleanup tool for macOS.
+//!
+//! Besides the `sukkiri` dashboard, the scanners can be used as a library:
+//!
+//! ```no_run
+//! use sukkiri::{CategoryFilter, CategoryType, ScanOptions};
+//!
+//! let options = ScanOptions {
+//!     categories: CategoryFilter::Only([CategoryType::NodeModules].into()),
+//!     ..ScanOptions::default()
+//! };
+//! for result in sukkiri::scan_all(&options)? {
+//!     println!("{}: {} bytes", result.category.name(), result.total_size);
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+mod allowlist;
+mod audit;
+mod cleaner;
+mod cli;
+mod config;
+mod constants;
+mod diff;
+mod home;
+mod interrupt;
+mod logging;
+mod model;
+mod pins;
+mod report;
+mod scanner;
+mod stats;
+mod ui;
+mod undo;
+mod units;
+
+pub use allowlist::Allowlist;
+/// Entry point of the `sukkiri` binary, not part of the library API.
+#[doc(hidden)]
+pub use cli::run;
+pub use model::{CategoryFilter, CategoryType, ScanResult, ScannedItem};
+pub use scanner::{ScanOptions, scan_all, scan_home};
@@ -0,0 +1,51 @@
+use anyhow::Result;
+use env_logger::{Target, WriteStyle};
+use log::LevelFilter;
+use std::fs::OpenOptions;
+use std::path::Path;
+
+/// Maps the number of `--verbose` flags to a log level.
+pub fn level_for(verbosity: u8) -> LevelFilter {
+    match verbosity {
+        0 => LevelFilter::Warn,
+        1 => LevelFilter::Info,
+        2 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    }
+}
+
+/// Sets up logging to `log_file`, or to stderr when running without the TUI.
+/// The dashboard owns the terminal, so without a log file nothing is logged there.
+pub fn init(verbosity: u8, log_file: Option<&Path>, interactive: bool) -> Result<()> {
+    let mut builder = env_logger::Builder::new();
+    builder.filter_level(level_for(verbosity));
+
+    match log_file {
+        Some(path) => {
+            let file = OpenOptions::new().create(true).append(true).open(path)?;
+            builder
+                .target(Target::Pipe(Box::new(file)))
+                .write_style(WriteStyle::Never);
+        }
+        None if interactive => return Ok(()),
+        None => {
+            builder.target(Target::Stderr);
+        }
+    }
+
+    builder.try_init()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verbosity_levels() {
+        assert_eq!(level_for(0), LevelFilter::Warn);
+        assert_eq!(level_for(1), LevelFilter::Info);
+        assert_eq!(level_for(2), LevelFilter::Debug);
+        assert_eq!(level_for(5), LevelFilter::Trace);
+    }
+}
@@ -1,5 +1,9 @@
-use std::path::PathBuf;
-use std::time::SystemTime;
+use crate::constants::{BUNDLE_EXTENSIONS, INSTALLER_EXTENSIONS};
+use clap::ValueEnum;
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum CategoryType {
@@ -14,11 +18,100 @@ pub enum CategoryType {
     DeveloperCaches,
     ScreenCapture,
     NodeModules,
-    #[allow(dead_code)]
+    BuildArtifacts,
+    QuickLookCache,
     DockerImages,
 }
 
+/// How likely cleaning a category is to remove something the user still wants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Risk {
+    /// Caches and build output that are regenerated automatically.
+    Low,
+    /// User files that are usually disposable, or permanent deletion.
+    Medium,
+    /// User files that may still be needed.
+    High,
+}
+
 impl CategoryType {
+    /// Every category, in the order scanners run and progress is listed.
+    #[must_use]
+    pub fn all() -> &'static [CategoryType] {
+        &[
+            Self::XcodeJunk,
+            Self::SystemLogs,
+            Self::SystemCache,
+            Self::UserLogs,
+            Self::UserCache,
+            Self::BrowserCache,
+            Self::Downloads,
+            Self::Trash,
+            Self::DeveloperCaches,
+            Self::ScreenCapture,
+            Self::NodeModules,
+            Self::BuildArtifacts,
+            Self::QuickLookCache,
+            Self::DockerImages,
+        ]
+    }
+
+    /// Position of this category in [`CategoryType::all`].
+    #[must_use]
+    pub fn order(self) -> usize {
+        Self::all()
+            .iter()
+            .position(|c| *c == self)
+            .unwrap_or(usize::MAX)
+    }
+
+    #[must_use]
+    pub fn risk(self) -> Risk {
+        match self {
+            Self::XcodeJunk
+            | Self::SystemLogs
+            | Self::SystemCache
+            | Self::UserLogs
+            | Self::UserCache
+            | Self::BrowserCache
+            | Self::DeveloperCaches
+            | Self::NodeModules
+            | Self::BuildArtifacts
+            | Self::QuickLookCache
+            | Self::DockerImages => Risk::Low,
+            Self::Trash | Self::ScreenCapture => Risk::Medium,
+            Self::Downloads => Risk::High,
+        }
+    }
+
+    /// Stable identifier used on the command line and in config files.
+    #[must_use]
+    pub fn slug(self) -> &'static str {
+        match self {
+            Self::XcodeJunk => "xcode-junk",
+            Self::SystemLogs => "system-logs",
+            Self::SystemCache => "system-cache",
+            Self::UserLogs => "user-logs",
+            Self::UserCache => "user-cache",
+            Self::BrowserCache => "browser-cache",
+            Self::Downloads => "downloads",
+            Self::Trash => "trash",
+            Self::DeveloperCaches => "developer-caches",
+            Self::ScreenCapture => "screen-capture",
+            Self::NodeModules => "node-modules",
+            Self::BuildArtifacts => "build-artifacts",
+            Self::QuickLookCache => "quicklook-cache",
+            Self::DockerImages => "docker-images",
+        }
+    }
+
+    #[must_use]
+    pub fn from_slug(slug: &str) -> Option<Self> {
+        let slug = slug.trim().to_ascii_lowercase();
+        Self::all().iter().copied().find(|c| c.slug() == slug)
+    }
+
+    #[must_use]
     pub fn name(self) -> &'static str {
         match self {
             Self::XcodeJunk => "Xcode Junk",
@@ -32,7 +125,86 @@ impl CategoryType {
             Self::DeveloperCaches => "Developer Caches",
             Self::ScreenCapture => "Screen Capture Files",
             Self::NodeModules => "Node Modules",
-            Self::DockerImages => "Docker Images",
+            Self::BuildArtifacts => "Build Artifacts",
+            Self::QuickLookCache => "QuickLook Thumbnails",
+            Self::DockerImages => "Container Images",
+        }
+    }
+
+    /// What the category holds. Build Artifacts also covers the folder names
+    /// configured in `[projects] artifact_dirs`, which this doesn't list.
+    #[must_use]
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::XcodeJunk => {
+                "Xcode build artifacts, archives, device support, and simulator caches."
+            }
+            Self::SystemLogs => "System log files (/Library/Logs, /private/var/log).",
+            Self::SystemCache => "System cache files.",
+            Self::UserLogs => "User log files.",
+            Self::UserCache => "User cache files (including sandboxed apps).",
+            Self::BrowserCache => "Web browser caches (Chrome, Safari, Firefox).",
+            Self::Downloads => "All files in Downloads folder.",
+            Self::Trash => "Trash folder contents.",
+            Self::DeveloperCaches => "Caches for npm, bun, pnpm, go, cargo, gradle, etc.",
+            Self::ScreenCapture => "Screenshots and screen recordings.",
+            Self::NodeModules => "Unused node_modules (recursively found in project directories).",
+            Self::BuildArtifacts => {
+                "Build output directories (target, .next, dist, ...) in project directories."
+            }
+            Self::QuickLookCache => "QuickLook thumbnail caches (rebuilt on demand).",
+            Self::DockerImages => "Unused Docker/Podman images (dangling=true).",
+        }
+    }
+}
+
+/// Which categories take part in a scan.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum CategoryFilter {
+    #[default]
+    All,
+    Only(HashSet<CategoryType>),
+    Exclude(HashSet<CategoryType>),
+}
+
+impl CategoryFilter {
+    #[must_use]
+    pub fn includes(&self, category: CategoryType) -> bool {
+        match self {
+            Self::All => true,
+            Self::Only(categories) => categories.contains(&category),
+            Self::Exclude(categories) => !categories.contains(&category),
+        }
+    }
+}
+
+/// Built-in category presets for `--profile`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Profile {
+    /// Trash, Xcode junk and browser caches
+    Minimal,
+    /// Minimal plus `node_modules`, container images and developer caches
+    Developer,
+    /// Every category, including Downloads
+    Aggressive,
+}
+
+impl Profile {
+    pub fn categories(self) -> CategoryFilter {
+        const MINIMAL: [CategoryType; 3] = [
+            CategoryType::Trash,
+            CategoryType::XcodeJunk,
+            CategoryType::BrowserCache,
+        ];
+        const DEVELOPER: [CategoryType; 3] = [
+            CategoryType::NodeModules,
+            CategoryType::DockerImages,
+            CategoryType::DeveloperCaches,
+        ];
+        match self {
+            Self::Minimal => CategoryFilter::Only(MINIMAL.into_iter().collect()),
+            Self::Developer => CategoryFilter::Only(MINIMAL.into_iter().chain(DEVELOPER).collect()),
+            Self::Aggressive => CategoryFilter::All,
         }
     }
 }
@@ -41,24 +213,430 @@ impl CategoryType {
 pub struct ScannedItem {
     pub path: PathBuf,
     pub size: u64,
-    #[allow(dead_code)]
     pub modified: SystemTime,
 }
 
+/// What a scanned item looks like, judging by its extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemKind {
+    /// Disk images, packages and archives such as `.dmg`, `.pkg` and `.zip`.
+    Installer,
+    /// Apps, plug-ins and frameworks such as `.app` and `.framework`.
+    Bundle,
+    Directory,
+    File,
+}
+
+impl ItemKind {
+    /// Classifies `path` by its extension, then by whether it is a directory.
+    pub fn classify(path: &Path, is_dir: bool) -> Self {
+        if has_extension(path, INSTALLER_EXTENSIONS) {
+            Self::Installer
+        } else if has_extension(path, BUNDLE_EXTENSIONS) {
+            Self::Bundle
+        } else if is_dir {
+            Self::Directory
+        } else {
+            Self::File
+        }
+    }
+
+    /// A short tag shown in front of item names.
+    pub fn tag(self) -> &'static str {
+        match self {
+            Self::Installer => "arc",
+            Self::Bundle => "app",
+            Self::Directory => "dir",
+            Self::File => "   ",
+        }
+    }
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension().is_some_and(|ext| {
+        extensions
+            .iter()
+            .any(|known| ext.eq_ignore_ascii_case(known))
+    })
+}
+
+impl ScannedItem {
+    /// Looks at the disk to tell directories from files, so only use it for items on screen.
+    #[must_use]
+    pub fn kind(&self) -> ItemKind {
+        ItemKind::classify(&self.path, self.path.is_dir())
+    }
+
+    #[must_use]
+    pub fn is_installer(&self) -> bool {
+        has_extension(&self.path, INSTALLER_EXTENSIONS)
+    }
+
+    /// Whether the item was modified less than `age` before `now` (or in the future).
+    #[must_use]
+    pub fn is_recent(&self, age: Duration, now: SystemTime) -> bool {
+        now.duration_since(self.modified)
+            .map_or(true, |elapsed| elapsed < age)
+    }
+}
+
+/// How the items of a category are ordered in the details pane.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ItemSort {
+    /// Largest first.
+    #[default]
+    Size,
+    /// Oldest first, for purging stale downloads and screenshots.
+    Age,
+    /// Alphabetically by file name.
+    Name,
+}
+
+impl ItemSort {
+    /// The next mode, wrapping around.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Size => Self::Age,
+            Self::Age => Self::Name,
+            Self::Name => Self::Size,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Size => "size",
+            Self::Age => "age",
+            Self::Name => "name",
+        }
+    }
+
+    pub fn sort(self, items: &mut [&ScannedItem]) {
+        match self {
+            Self::Size => items.sort_by_key(|i| Reverse(i.size)),
+            Self::Age => items.sort_by_key(|i| i.modified),
+            Self::Name => items.sort_by_cached_key(|i| {
+                i.path
+                    .file_name()
+                    .unwrap_or(i.path.as_os_str())
+                    .to_string_lossy()
+                    .to_lowercase()
+            }),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
-#[allow(dead_code)]
 pub struct ScanResult {
     pub category: CategoryType,
     pub total_size: u64,
     pub items: Vec<ScannedItem>,
     pub is_selected: bool,
-    pub description: String,
+    /// Where the category is shown to live: its main folder, or for searches through
+    /// several project roots, where those roots meet.
     pub root_path: PathBuf,
+    /// Every existing location the category was scanned from.
+    pub roots: Vec<PathBuf>,
+    /// The scan hit its time budget, so this is only part of the category.
+    pub timed_out: bool,
+    /// How long the category took to scan.
+    pub duration: Duration,
+    /// Files offloaded to iCloud that were skipped rather than downloaded.
+    pub offloaded: usize,
+    /// Why parts of the category couldn't be scanned, e.g. unreadable directories.
+    pub warnings: Vec<String>,
+    /// Why the category came back as it did, e.g. that Docker's daemon isn't running.
+    pub note: Option<String>,
+    /// Items holding files that running apps have open (`--skip-open-files`).
+    pub in_use: Vec<PathBuf>,
+}
+
+impl ScanResult {
+    /// The `items` of `category` found below `root`, largest first.
+    #[must_use]
+    pub fn new(category: CategoryType, root: PathBuf, mut items: Vec<ScannedItem>) -> Self {
+        items.sort_by_key(|i| Reverse(i.size));
+        Self {
+            category,
+            total_size: items.iter().map(|i| i.size).sum(),
+            items,
+            is_selected: false,
+            root_path: root.clone(),
+            roots: vec![root],
+            timed_out: false,
+            duration: Duration::ZERO,
+            offloaded: 0,
+            warnings: Vec::new(),
+            note: None,
+            in_use: Vec::new(),
+        }
+    }
+
+    /// Combines results of the same category scanned from other roots into this one.
+    /// Items are re-sorted largest first and the total is recomputed; `root_path`
+    /// stays this result's, so `o` and `y` still point at the main folder.
+    #[must_use]
+    pub fn merge(mut self, others: impl IntoIterator<Item = Self>) -> Self {
+        for other in others {
+            debug_assert_eq!(self.category, other.category);
+            self.items.extend(other.items);
+            self.roots.extend(other.roots);
+            self.timed_out |= other.timed_out;
+            self.duration += other.duration;
+            self.offloaded += other.offloaded;
+            self.warnings.extend(other.warnings);
+            self.note = self.note.or(other.note);
+            self.in_use.extend(other.in_use);
+        }
+        self.items.sort_by_key(|i| Reverse(i.size));
+        self.total_size = self.items.iter().map(|i| i.size).sum();
+        self
+    }
+}
+
+/// The deepest directory containing every path, used to display several roots as one.
+pub fn common_ancestor(paths: &[PathBuf]) -> PathBuf {
+    let mut paths = paths.iter();
+    let Some(first) = paths.next() else {
+        return PathBuf::new();
+    };
+    paths.fold(first.clone(), |ancestor, path| {
+        ancestor
+            .ancestors()
+            .find(|candidate| path.starts_with(candidate))
+            .map(Path::to_path_buf)
+            .unwrap_or_default()
+    })
 }
 
 #[derive(Debug, Clone)]
 pub struct ScanProgress {
     pub category: CategoryType,
-    pub items_count: u64,
-    pub status: String, // e.g., "Scanning..." or "Done"
+    pub items_count: u64,   // items processed so far
+    pub items_total: u64,   // items discovered so far
+    pub bytes: u64,         // size of the processed items so far
+    pub status: String,     // e.g., "Scanning..." or "Done"
+    pub duration: Duration, // how long the category took, once done
+}
+
+impl ScanProgress {
+    pub fn is_done(&self) -> bool {
+        self.status == "Done"
+    }
+
+    /// Fraction of this category's discovered items that have been processed.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn ratio(&self) -> f64 {
+        if self.is_done() {
+            1.0
+        } else if self.items_total == 0 {
+            0.0
+        } else {
+            (self.items_count as f64 / self.items_total as f64).clamp(0.0, 1.0)
+        }
+    }
+}
+
+/// Reported by scanners through their progress callback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressEvent {
+    /// This many more items were found and will be processed.
+    Found(u64),
+    /// One found item of this many bytes has been processed.
+    Done(u64),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_results_from_several_roots() {
+        let item = |path: &str, size| ScannedItem {
+            path: PathBuf::from(path),
+            size,
+            modified: SystemTime::UNIX_EPOCH,
+        };
+        let logs = ScanResult::new(
+            CategoryType::SystemLogs,
+            PathBuf::from("/Library/Logs"),
+            vec![item("/Library/Logs/a.log", 100)],
+        );
+        let mut var_log = ScanResult::new(
+            CategoryType::SystemLogs,
+            PathBuf::from("/private/var/log"),
+            vec![
+                item("/private/var/log/b.log", 50),
+                item("/private/var/log/c.log", 300),
+            ],
+        );
+        var_log.timed_out = true;
+        var_log.warnings = vec!["1 directory could not be read".to_string()];
+
+        let merged = logs.merge([var_log]);
+        assert_eq!(merged.total_size, 450);
+        let sizes: Vec<u64> = merged.items.iter().map(|i| i.size).collect();
+        assert_eq!(sizes, vec![300, 100, 50]);
+        assert_eq!(
+            merged.roots,
+            vec![
+                PathBuf::from("/Library/Logs"),
+                PathBuf::from("/private/var/log")
+            ]
+        );
+        assert_eq!(merged.root_path, PathBuf::from("/Library/Logs"));
+        assert!(merged.timed_out);
+        assert_eq!(merged.warnings.len(), 1);
+    }
+
+    #[test]
+    fn every_category_has_a_description() {
+        for category in CategoryType::all() {
+            let description = category.description();
+            assert!(!description.is_empty(), "{category:?}");
+            assert!(description.ends_with('.'), "{category:?}");
+        }
+    }
+
+    #[test]
+    fn common_ancestor_of_roots() {
+        assert_eq!(
+            common_ancestor(&[PathBuf::from("/Users/a/dev")]),
+            PathBuf::from("/Users/a/dev")
+        );
+        assert_eq!(
+            common_ancestor(&[
+                PathBuf::from("/Users/a/Projects"),
+                PathBuf::from("/Users/a/dev/x")
+            ]),
+            PathBuf::from("/Users/a")
+        );
+    }
+
+    #[test]
+    fn classifies_items_by_extension_and_type() {
+        let kind = |path: &str, is_dir| ItemKind::classify(Path::new(path), is_dir);
+        assert_eq!(kind("/dl/Xcode_15.xip", false), ItemKind::Installer);
+        assert_eq!(kind("/dl/Tool.DMG", false), ItemKind::Installer);
+        assert_eq!(kind("/Applications/Old.app", true), ItemKind::Bundle);
+        assert_eq!(kind("/dd/Build/Foo.framework", true), ItemKind::Bundle);
+        assert_eq!(kind("/cache/com.apple.Safari", true), ItemKind::Directory);
+        assert_eq!(kind("/logs/system.log", false), ItemKind::File);
+        assert_eq!(kind("docker://abc123/app:old", false), ItemKind::File);
+        assert_eq!(ItemKind::Directory.tag(), "dir");
+    }
+
+    #[test]
+    fn all_lists_every_variant_once() {
+        // Exhaustive so that adding a variant fails to compile until it is listed here
+        fn index(category: CategoryType) -> usize {
+            match category {
+                CategoryType::XcodeJunk => 0,
+                CategoryType::SystemLogs => 1,
+                CategoryType::SystemCache => 2,
+                CategoryType::UserLogs => 3,
+                CategoryType::UserCache => 4,
+                CategoryType::BrowserCache => 5,
+                CategoryType::Downloads => 6,
+                CategoryType::Trash => 7,
+                CategoryType::DeveloperCaches => 8,
+                CategoryType::ScreenCapture => 9,
+                CategoryType::NodeModules => 10,
+                CategoryType::BuildArtifacts => 11,
+                CategoryType::QuickLookCache => 12,
+                CategoryType::DockerImages => 13,
+            }
+        }
+
+        assert_eq!(CategoryType::all().len(), 14);
+        for (i, category) in CategoryType::all().iter().enumerate() {
+            assert_eq!(index(*category), i);
+            assert_eq!(category.order(), i);
+        }
+    }
+
+    #[test]
+    fn slug_round_trip() {
+        for &category in CategoryType::all() {
+            assert_eq!(CategoryType::from_slug(category.slug()), Some(category));
+        }
+        assert_eq!(
+            CategoryType::from_slug(" Node-Modules "),
+            Some(CategoryType::NodeModules)
+        );
+        assert_eq!(CategoryType::from_slug("node_modules"), None);
+    }
+
+    #[test]
+    fn profile_categories() {
+        let minimal = HashSet::from([
+            CategoryType::Trash,
+            CategoryType::XcodeJunk,
+            CategoryType::BrowserCache,
+        ]);
+        assert_eq!(
+            Profile::Minimal.categories(),
+            CategoryFilter::Only(minimal.clone())
+        );
+
+        let mut developer = minimal;
+        developer.extend([
+            CategoryType::NodeModules,
+            CategoryType::DockerImages,
+            CategoryType::DeveloperCaches,
+        ]);
+        assert_eq!(
+            Profile::Developer.categories(),
+            CategoryFilter::Only(developer)
+        );
+
+        assert_eq!(Profile::Aggressive.categories(), CategoryFilter::All);
+    }
+
+    #[test]
+    fn item_sort_orders_by_age_and_name() {
+        let item = |path: &str, size, secs| ScannedItem {
+            path: PathBuf::from(path),
+            size,
+            modified: SystemTime::UNIX_EPOCH + Duration::from_secs(secs),
+        };
+        let items = [
+            item("/dl/b.zip", 30, 200),
+            item("/dl/C.dmg", 10, 100),
+            item("/dl/a.pdf", 20, 300),
+        ];
+        let sorted = |sort: ItemSort| {
+            let mut refs: Vec<&ScannedItem> = items.iter().collect();
+            sort.sort(&mut refs);
+            refs.iter()
+                .map(|i| i.path.to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            sorted(ItemSort::Age),
+            vec!["/dl/C.dmg", "/dl/b.zip", "/dl/a.pdf"]
+        );
+        assert_eq!(
+            sorted(ItemSort::Size),
+            vec!["/dl/b.zip", "/dl/a.pdf", "/dl/C.dmg"]
+        );
+        assert_eq!(
+            sorted(ItemSort::Name),
+            vec!["/dl/a.pdf", "/dl/b.zip", "/dl/C.dmg"]
+        );
+        assert_eq!(ItemSort::Name.next(), ItemSort::Size);
+    }
+
+    #[test]
+    fn category_filter_includes() {
+        let only = CategoryFilter::Only(HashSet::from([CategoryType::Trash]));
+        assert!(only.includes(CategoryType::Trash));
+        assert!(!only.includes(CategoryType::Downloads));
+
+        let exclude = CategoryFilter::Exclude(HashSet::from([CategoryType::Downloads]));
+        assert!(exclude.includes(CategoryType::Trash));
+        assert!(!exclude.includes(CategoryType::Downloads));
+
+        assert!(CategoryFilter::All.includes(CategoryType::Downloads));
+    }
 }
@@ -0,0 +1,95 @@
+use crate::config::config_dir;
+use crate::model::CategoryType;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File in the configuration directory listing the categories pinned to the top.
+const PINS_FILE: &str = "pinned.toml";
+
+/// Categories pinned with `p`, by slug.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pins {
+    #[serde(default)]
+    pub categories: Vec<String>,
+}
+
+impl Pins {
+    pub fn parse(content: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(content)
+    }
+
+    pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string(self)
+    }
+
+    pub fn from_set(pinned: &HashSet<CategoryType>) -> Self {
+        let mut categories: Vec<CategoryType> = pinned.iter().copied().collect();
+        categories.sort_by_key(|c| c.order());
+        Self {
+            categories: categories.iter().map(|c| c.slug().to_string()).collect(),
+        }
+    }
+
+    /// The pinned categories, ignoring unknown slugs.
+    pub fn to_set(&self) -> HashSet<CategoryType> {
+        self.categories
+            .iter()
+            .filter_map(|slug| CategoryType::from_slug(slug))
+            .collect()
+    }
+}
+
+fn pins_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join(PINS_FILE))
+}
+
+/// Loads the pinned categories. Returns none if the file doesn't exist or errors.
+pub fn load() -> HashSet<CategoryType> {
+    pins_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| Pins::parse(&content).ok())
+        .map(|pins| pins.to_set())
+        .unwrap_or_default()
+}
+
+/// Remembers the pinned categories for the next run.
+pub fn save(pinned: &HashSet<CategoryType>) {
+    let Some(path) = pins_path() else {
+        return;
+    };
+    if let Err(e) = write(&path, &Pins::from_set(pinned)) {
+        warn!("Failed to write {}: {e}", path.display());
+    }
+}
+
+fn write(path: &Path, pins: &Pins) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, pins.to_toml()?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pins_round_trip() {
+        let pinned = HashSet::from([CategoryType::Trash, CategoryType::XcodeJunk]);
+        let pins = Pins::from_set(&pinned);
+        assert_eq!(pins.categories, vec!["xcode-junk", "trash"]);
+
+        let content = pins.to_toml().unwrap();
+        assert_eq!(Pins::parse(&content).unwrap().to_set(), pinned);
+        assert_eq!(
+            Pins::parse(r#"categories = ["trash", "bogus"]"#)
+                .unwrap()
+                .to_set(),
+            HashSet::from([CategoryType::Trash])
+        );
+    }
+}
@@ -0,0 +1,311 @@
+use crate::constants::HTML_TOP_ITEMS;
+use crate::model::{ScanResult, ScannedItem};
+use crate::scanner::container::ContainerEngine;
+use crate::units::Units;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+/// Shown instead of the results when the scan found nothing.
+pub const NOTHING_TO_CLEAN: &str = "Nothing to clean — your system is tidy ✨";
+
+/// Whether every category came back empty (fresh machine, everything allowlisted).
+pub fn nothing_found(results: &[ScanResult]) -> bool {
+    results.iter().all(|r| r.items.is_empty())
+}
+
+/// Formats scan results as an unstyled plain-text table with a grand total.
+pub fn text_summary(results: &[ScanResult], units: Units) -> String {
+    if nothing_found(results) {
+        return format!("{NOTHING_TO_CLEAN}\n");
+    }
+    let mut out = String::new();
+    for result in results {
+        let partial = if result.timed_out {
+            " (partial, timed out)"
+        } else {
+            ""
+        };
+        let _ = writeln!(
+            out,
+            "{:<22} {:>12} {:>8} items{partial}",
+            result.category.name(),
+            units.format(result.total_size),
+            result.items.len()
+        );
+    }
+    let total: u64 = results.iter().map(|r| r.total_size).sum();
+    let count: usize = results.iter().map(|r| r.items.len()).sum();
+    let _ = writeln!(
+        out,
+        "{:<22} {:>12} {count:>8} items",
+        "Total",
+        units.format(total)
+    );
+    out
+}
+
+/// The JSON report of `sukkiri audit --json`, also read back by `--since`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JsonSummary {
+    pub total_size: u64,
+    pub total_items: usize,
+    pub categories: Vec<JsonCategory>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JsonCategory {
+    /// The category's slug, as in `--only`.
+    pub category: String,
+    pub name: String,
+    pub size: u64,
+    pub items: usize,
+    pub root_path: PathBuf,
+    pub timed_out: bool,
+    /// Every item with its size. Missing from reports of older versions.
+    #[serde(default)]
+    pub entries: Vec<JsonItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JsonItem {
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+/// Formats scan results as JSON for scripts and dashboards. Sizes are in bytes.
+pub fn json_summary(results: &[ScanResult]) -> serde_json::Result<String> {
+    let summary = JsonSummary {
+        total_size: results.iter().map(|r| r.total_size).sum(),
+        total_items: results.iter().map(|r| r.items.len()).sum(),
+        categories: results
+            .iter()
+            .map(|r| JsonCategory {
+                category: r.category.slug().to_string(),
+                name: r.category.name().to_string(),
+                size: r.total_size,
+                items: r.items.len(),
+                root_path: r.root_path.clone(),
+                timed_out: r.timed_out,
+                entries: r
+                    .items
+                    .iter()
+                    .map(|item| JsonItem {
+                        path: item.path.clone(),
+                        size: item.size,
+                    })
+                    .collect(),
+            })
+            .collect(),
+    };
+    serde_json::to_string_pretty(&summary)
+}
+
+/// Reads a report written by [`json_summary`].
+pub fn parse_json_summary(json: &str) -> serde_json::Result<JsonSummary> {
+    serde_json::from_str(json)
+}
+
+/// Lists every item one per line (`--print-paths`), for piping into `xargs rm`.
+/// Container images are listed as the command that removes them.
+pub fn path_list(items: &[ScannedItem]) -> String {
+    let mut out = String::new();
+    for item in items {
+        let _ = match ContainerEngine::parse_path(&item.path) {
+            Some((engine, id)) => writeln!(out, "{} rmi {id}", engine.binary()),
+            None => writeln!(out, "{}", item.path.display()),
+        };
+    }
+    out
+}
+
+/// Escapes text for HTML, so odd file names can't inject markup.
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+const HTML_STYLE: &str = "body{font-family:-apple-system,sans-serif;margin:2em;color:#222}\
+table{border-collapse:collapse;margin-bottom:2em}td,th{padding:4px 12px;text-align:left}\
+td.size{text-align:right;white-space:nowrap}.bar{background:#4a9;height:12px}\
+tr:nth-child(even){background:#f4f4f4}";
+
+/// Formats scan results as a self-contained HTML page: the categories with a
+/// bar for their share of the total, then the largest items across all of them.
+#[allow(clippy::cast_precision_loss)]
+pub fn html_report(results: &[ScanResult], units: Units) -> String {
+    let total: u64 = results.iter().map(|r| r.total_size).sum();
+    let largest = results
+        .iter()
+        .map(|r| r.total_size)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let mut out = String::new();
+    let _ = write!(
+        out,
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>sukkiri report</title>\
+         <style>{HTML_STYLE}</style></head><body>\n<h1>sukkiri report</h1>\n\
+         <p>Total: {}</p>\n<table>\n<tr><th>Category</th><th>Size</th><th>Items</th><th></th></tr>\n",
+        units.format(total)
+    );
+    for result in results {
+        let percent = result.total_size as f64 / largest as f64 * 100.0;
+        let _ = writeln!(
+            out,
+            "<tr><td>{}</td><td class=\"size\">{}</td><td>{}</td>\
+             <td><div class=\"bar\" style=\"width:{percent:.0}px\"></div></td></tr>",
+            escape_html(result.category.name()),
+            units.format(result.total_size),
+            result.items.len()
+        );
+    }
+    out.push_str(
+        "</table>\n<h2>Largest items</h2>\n<table>\n<tr><th>Path</th><th>Size</th></tr>\n",
+    );
+    let mut items: Vec<_> = results.iter().flat_map(|r| &r.items).collect();
+    items.sort_by_key(|i| Reverse(i.size));
+    for item in items.into_iter().take(HTML_TOP_ITEMS) {
+        let _ = writeln!(
+            out,
+            "<tr><td>{}</td><td class=\"size\">{}</td></tr>",
+            escape_html(&item.path.to_string_lossy()),
+            units.format(item.size)
+        );
+    }
+    out.push_str("</table>\n</body></html>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::CategoryType;
+    use std::time::{Duration, SystemTime};
+
+    fn result(category: CategoryType, sizes: &[u64], timed_out: bool) -> ScanResult {
+        let items: Vec<ScannedItem> = sizes
+            .iter()
+            .map(|&size| ScannedItem {
+                path: PathBuf::from("/tmp/item"),
+                size,
+                modified: SystemTime::UNIX_EPOCH,
+            })
+            .collect();
+        ScanResult {
+            category,
+            total_size: sizes.iter().sum(),
+            items,
+            is_selected: false,
+            root_path: PathBuf::from("/tmp"),
+            roots: Vec::new(),
+            timed_out,
+            duration: Duration::ZERO,
+            offloaded: 0,
+            warnings: Vec::new(),
+            note: None,
+            in_use: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn empty_results() {
+        assert!(nothing_found(&[]));
+        let empty = vec![
+            result(CategoryType::Trash, &[], false),
+            result(CategoryType::Downloads, &[], false),
+        ];
+        assert!(nothing_found(&empty));
+        assert_eq!(
+            text_summary(&empty, Units::Binary),
+            format!("{NOTHING_TO_CLEAN}\n")
+        );
+        assert!(!nothing_found(&[result(CategoryType::Trash, &[0], false)]));
+    }
+
+    #[test]
+    fn summary_table() {
+        let results = vec![
+            result(CategoryType::NodeModules, &[1 << 30, 1 << 29], false),
+            result(CategoryType::Trash, &[512], true),
+        ];
+        assert_eq!(
+            text_summary(&results, Units::Binary),
+            "\
+Node Modules               1.50 GiB        2 items
+Trash                         512 B        1 items (partial, timed out)
+Total                      1.50 GiB        3 items
+"
+        );
+    }
+
+    #[test]
+    fn json_summary_fields() -> serde_json::Result<()> {
+        let results = vec![result(CategoryType::Trash, &[512, 256], true)];
+        let json: serde_json::Value = serde_json::from_str(&json_summary(&results)?)?;
+        assert_eq!(json["total_size"], 768);
+        assert_eq!(json["total_items"], 2);
+        assert_eq!(json["categories"][0]["category"], "trash");
+        assert_eq!(json["categories"][0]["name"], "Trash");
+        assert_eq!(json["categories"][0]["root_path"], "/tmp");
+        assert_eq!(json["categories"][0]["timed_out"], true);
+        assert_eq!(json["categories"][0]["entries"][1]["size"], 256);
+
+        let parsed = parse_json_summary(&json.to_string())?;
+        assert_eq!(parsed.categories[0].entries.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn html_report_escapes_paths() {
+        let mut downloads = result(CategoryType::Downloads, &[2048], false);
+        downloads.items[0].path = PathBuf::from("/dl/<script>alert(1)</script>.dmg");
+        let results = vec![downloads, result(CategoryType::Trash, &[512], false)];
+
+        let html = html_report(&results, Units::Binary);
+        assert!(html.contains("<td>Downloads</td>"));
+        assert!(html.contains("<td>Trash</td>"));
+        assert!(html.contains("/dl/&lt;script&gt;alert(1)&lt;/script&gt;.dmg"));
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("Total: 2.50 KiB"));
+    }
+
+    #[test]
+    fn path_list_prints_one_path_per_line() {
+        let items: Vec<ScannedItem> = [
+            "/Users/me/Library/Caches/com.apple.Safari",
+            "/Users/me/Projects/app/node_modules",
+            "docker://4e1a2b3c/nginx:latest",
+            "podman://9f2c7d/<none>:<none>",
+        ]
+        .into_iter()
+        .map(|path| ScannedItem {
+            path: PathBuf::from(path),
+            size: 1,
+            modified: SystemTime::UNIX_EPOCH,
+        })
+        .collect();
+
+        assert_eq!(
+            path_list(&items),
+            "\
+/Users/me/Library/Caches/com.apple.Safari
+/Users/me/Projects/app/node_modules
+docker rmi 4e1a2b3c
+podman rmi 9f2c7d
+"
+        );
+        assert_eq!(path_list(&[]), "");
+    }
+}
@@ -26,7 +26,7 @@ pub fn browser_cache_scanner(home: &Path) -> PathScanner {
 
     PathScanner {
         category: CategoryType::BrowserCache,
-        description: "Web browser caches (Chrome, Safari, Firefox).".to_string(),
         paths,
+        flatten_files: false,
     }
 }
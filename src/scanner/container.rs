@@ -0,0 +1,461 @@
+use crate::model::{CategoryType, ProgressEvent, ScanResult, ScannedItem};
+use crate::scanner::{ProgressFn, ScanOptions, Scanner};
+use log::{debug, warn};
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::time::SystemTime;
+
+/// A container CLI that can list and remove images.
+/// Colima exposes the `docker` CLI, so it is covered by `Docker`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContainerEngine {
+    Docker,
+    Podman,
+}
+
+impl ContainerEngine {
+    pub const ALL: [Self; 2] = [Self::Docker, Self::Podman];
+
+    pub fn binary(self) -> &'static str {
+        match self {
+            Self::Docker => "docker",
+            Self::Podman => "podman",
+        }
+    }
+
+    /// Prefix of the virtual paths for this engine's images, e.g. `podman://`.
+    pub fn scheme(self) -> &'static str {
+        match self {
+            Self::Docker => "docker://",
+            Self::Podman => "podman://",
+        }
+    }
+
+    /// Splits a virtual image path (`<engine>://<ID>/<Name>`) into its engine and image ID.
+    pub fn parse_path(path: &Path) -> Option<(Self, String)> {
+        let path_str = path.to_string_lossy();
+        Self::ALL.into_iter().find_map(|engine| {
+            let rest = path_str.strip_prefix(engine.scheme())?;
+            // Extract ID (part before the first slash)
+            let id = rest.split('/').next().unwrap_or(rest);
+            Some((engine, id.to_string()))
+        })
+    }
+
+    /// The `<Repository>:<Tag>` part of a virtual image path, e.g. `nginx:<none>`.
+    pub fn image_name(path: &Path) -> Option<String> {
+        let path_str = path.to_string_lossy();
+        Self::ALL.into_iter().find_map(|engine| {
+            let rest = path_str.strip_prefix(engine.scheme())?;
+            rest.split_once('/').map(|(_, name)| name.to_string())
+        })
+    }
+}
+
+/// What asking an engine for its dangling images came back with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EngineStatus {
+    /// The CLI isn't on `PATH` or can't be run.
+    NotInstalled,
+    /// The CLI ran but couldn't reach its daemon (or VM).
+    DaemonDown,
+    /// The daemon answered without any dangling images.
+    NothingToClean,
+    /// This many dangling images were listed.
+    Found(usize),
+}
+
+impl EngineStatus {
+    fn from_output(output: &io::Result<Output>) -> Self {
+        match output {
+            Err(_) => Self::NotInstalled,
+            Ok(output) if !output.status.success() => Self::DaemonDown,
+            Ok(output) => match String::from_utf8_lossy(&output.stdout).lines().count() {
+                0 => Self::NothingToClean,
+                count => Self::Found(count),
+            },
+        }
+    }
+
+    /// Why the engine added nothing to the category, if it didn't.
+    pub fn label(self) -> Option<&'static str> {
+        match self {
+            Self::NotInstalled => Some("not installed"),
+            Self::DaemonDown => Some("daemon not running"),
+            Self::NothingToClean => Some("no dangling images"),
+            Self::Found(_) => None,
+        }
+    }
+}
+
+/// The category note for what each engine came back with, so an empty category
+/// tells a missing CLI, a stopped daemon and a clean machine apart.
+fn status_note(statuses: &[(ContainerEngine, EngineStatus)]) -> Option<String> {
+    if statuses.is_empty() {
+        let binaries: Vec<&str> = ContainerEngine::ALL.iter().map(|e| e.binary()).collect();
+        return Some(format!("{} not installed", binaries.join(" and ")));
+    }
+    let notes: Vec<String> = statuses
+        .iter()
+        .filter_map(|(engine, status)| Some(format!("{} {}", engine.binary(), status.label()?)))
+        .collect();
+    (!notes.is_empty()).then(|| notes.join(", "))
+}
+
+pub struct ContainerScanner {
+    /// Directories searched for engine binaries, formatted like `$PATH`.
+    pub search_path: OsString,
+}
+
+impl ContainerScanner {
+    pub fn from_env() -> Self {
+        Self {
+            search_path: std::env::var_os("PATH").unwrap_or_default(),
+        }
+    }
+}
+
+impl Scanner for ContainerScanner {
+    fn category(&self) -> CategoryType {
+        CategoryType::DockerImages
+    }
+
+    fn uses_external_commands(&self) -> bool {
+        true
+    }
+
+    fn scan(&self, progress_cb: Option<&ProgressFn>, options: &ScanOptions) -> ScanResult {
+        let path = PathBuf::from("Containers"); // Virtual path
+        if options.no_external_commands {
+            return ScanResult {
+                note: Some("not checked without external commands".to_string()),
+                ..ScanResult::new(self.category(), path, Vec::new())
+            };
+        }
+
+        // Container scanning via each available CLI
+        let mut statuses = Vec::new();
+        let mut items = Vec::new();
+        for (engine, binary) in find_engines(&self.search_path) {
+            let (status, images) = scan_unused_images(engine, &binary, progress_cb);
+            statuses.push((engine, status));
+            items.extend(images);
+        }
+        items.retain(|i| !options.allowlist.is_allowed(&i.path));
+
+        ScanResult {
+            note: status_note(&statuses),
+            ..ScanResult::new(self.category(), path, items)
+        }
+    }
+}
+
+/// Returns the engines whose binary is an executable in `search_path`.
+fn find_engines(search_path: &OsString) -> Vec<(ContainerEngine, PathBuf)> {
+    ContainerEngine::ALL
+        .into_iter()
+        .filter_map(|engine| {
+            std::env::split_paths(search_path)
+                .map(|dir| dir.join(engine.binary()))
+                .find(|candidate| is_executable(candidate))
+                .map(|binary| (engine, binary))
+        })
+        .collect()
+}
+
+fn is_executable(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+fn scan_unused_images(
+    engine: ContainerEngine,
+    binary: &Path,
+    progress_cb: Option<&ProgressFn>,
+) -> (EngineStatus, Vec<ScannedItem>) {
+    // <engine> images -f "dangling=true" --format "{{.ID}}|{{.Size}}|{{.Repository}}:{{.Tag}}|{{.CreatedAt}}"
+    let output = Command::new(binary)
+        .args([
+            "images",
+            "-f",
+            "dangling=true",
+            "--format",
+            "{{.ID}}|{{.Size}}|{{.Repository}}:{{.Tag}}|{{.CreatedAt}}",
+        ])
+        .output();
+
+    let status = EngineStatus::from_output(&output);
+    let output = match output {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            warn!(
+                "{} images failed: {}",
+                engine.binary(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            return (status, vec![]);
+        }
+        Err(e) => {
+            warn!("Failed to execute {}: {e}", engine.binary());
+            return (status, vec![]);
+        }
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let mut items = Vec::new();
+
+    if let Some(cb) = progress_cb {
+        cb(ProgressEvent::Found(stdout.lines().count() as u64));
+    }
+
+    for line in stdout.lines() {
+        let parts: Vec<&str> = line.split('|').collect();
+        if parts.len() >= 2 {
+            let id = parts[0];
+            let size_str = parts[1];
+            let name = if parts.len() > 2 { parts[2] } else { "<none>" };
+            let created = parts
+                .get(3)
+                .and_then(|created| parse_created_at(created))
+                .unwrap_or_else(SystemTime::now);
+
+            let size = parse_docker_size(size_str);
+
+            // Images don't have a real path, so we make a virtual one
+            let path = PathBuf::from(format!("{}{id}/{name}", engine.scheme()));
+
+            if let Some(cb) = progress_cb {
+                cb(ProgressEvent::Done(size));
+            }
+
+            items.push(ScannedItem {
+                path,
+                size,
+                modified: created,
+            });
+        }
+    }
+
+    (status, items)
+}
+
+/// Parses an image's `CreatedAt`, e.g. `2024-03-01 09:30:00 +0100 CET`.
+/// Podman adds fractional seconds (`09:30:00.123456789`), which are ignored.
+fn parse_created_at(created: &str) -> Option<SystemTime> {
+    let mut fields = created.split_whitespace();
+    let mut date = fields.next()?.splitn(3, '-').map(str::parse::<i64>);
+    let (year, month, day) = (date.next()?.ok()?, date.next()?.ok()?, date.next()?.ok()?);
+    let time = fields.next()?.split('.').next()?;
+    let mut time = time.splitn(3, ':').map(str::parse::<i64>);
+    let (hour, minute, second) = (time.next()?.ok()?, time.next()?.ok()?, time.next()?.ok()?);
+    let offset = fields.next().map_or(Some(0), parse_utc_offset)?;
+
+    let secs =
+        days_from_civil(year, month, day) * 86_400 + hour * 3_600 + minute * 60 + second - offset;
+    let secs = u64::try_from(secs).ok()?;
+    Some(SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(secs))
+}
+
+/// Parses a `+hhmm` / `-hhmm` UTC offset into seconds.
+fn parse_utc_offset(offset: &str) -> Option<i64> {
+    let (sign, digits) = match offset.split_at_checked(1)? {
+        ("+", digits) => (1, digits),
+        ("-", digits) => (-1, digits),
+        _ => return None,
+    };
+    let hours: i64 = digits.get(..2)?.parse().ok()?;
+    let minutes: i64 = digits.get(2..4)?.parse().ok()?;
+    Some(sign * (hours * 3_600 + minutes * 60))
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date (Howard Hinnant's algorithm).
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Parses a size as printed by `docker images`, e.g. `1.5GB`, `1.2kB` or `10 MB`.
+/// Docker prints decimal units (1kB = 1000 bytes). Unknown formats count as 0.
+fn parse_docker_size(size_str: &str) -> u64 {
+    let s: String = size_str
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_uppercase();
+    let split = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let multiplier: f64 = match unit {
+        "" | "B" => 1.0,
+        "KB" => 1e3,
+        "MB" => 1e6,
+        "GB" => 1e9,
+        "TB" => 1e12,
+        _ => {
+            debug!("Unrecognized image size {size_str:?}");
+            return 0;
+        }
+    };
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    number.parse::<f64>().map_or(0, |v| (v * multiplier) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::ExitStatus;
+    use std::time::Duration;
+    use tempfile::tempdir;
+
+    #[test]
+    fn parse_docker_size_test() {
+        assert_eq!(parse_docker_size("1KB"), 1_000);
+        assert_eq!(parse_docker_size("1MB"), 1_000_000);
+        assert_eq!(parse_docker_size("1.5GB"), 1_500_000_000);
+        assert_eq!(parse_docker_size("500B"), 500);
+        assert_eq!(parse_docker_size("0B"), 0);
+    }
+
+    #[test]
+    fn parse_docker_size_tolerates_spaces_and_case() {
+        assert_eq!(parse_docker_size("1.2 GB"), 1_200_000_000);
+        assert_eq!(parse_docker_size("1.2kB"), 1_200);
+        assert_eq!(parse_docker_size("10 MB"), 10_000_000);
+        assert_eq!(parse_docker_size(" 2 tb "), 2_000_000_000_000);
+        assert_eq!(parse_docker_size("huge"), 0);
+        assert_eq!(parse_docker_size("12 parsecs"), 0);
+    }
+
+    #[test]
+    fn parse_created_at_formats() {
+        let at = |secs| Some(SystemTime::UNIX_EPOCH + Duration::from_secs(secs));
+        assert_eq!(
+            parse_created_at("1970-01-02 00:00:00 +0000 UTC"),
+            at(86_400)
+        );
+        assert_eq!(
+            parse_created_at("2024-03-01 09:30:00 +0100 CET"),
+            at(1_709_281_800)
+        );
+        assert_eq!(
+            parse_created_at("2024-03-01 08:30:00.123456789 +0000 UTC"),
+            at(1_709_281_800)
+        );
+        assert_eq!(parse_created_at("yesterday"), None);
+    }
+
+    #[test]
+    fn parse_virtual_paths() {
+        assert_eq!(
+            ContainerEngine::parse_path(Path::new("podman://abc123/<none>:<none>")),
+            Some((ContainerEngine::Podman, "abc123".to_string()))
+        );
+        assert_eq!(
+            ContainerEngine::parse_path(Path::new("docker://def456/app:old")),
+            Some((ContainerEngine::Docker, "def456".to_string()))
+        );
+        assert_eq!(ContainerEngine::parse_path(Path::new("/tmp/file")), None);
+        assert_eq!(
+            ContainerEngine::image_name(Path::new("docker://def456/library/nginx:<none>")),
+            Some("library/nginx:<none>".to_string())
+        );
+        assert_eq!(ContainerEngine::image_name(Path::new("/tmp/file")), None);
+    }
+
+    #[test]
+    fn uses_podman_when_docker_is_missing() -> anyhow::Result<()> {
+        let bin = tempdir()?;
+        let podman = bin.path().join("podman");
+        let mut script = fs::File::create(&podman)?;
+        writeln!(script, "#!/bin/sh")?;
+        writeln!(script, "echo 'abc123|1MB|<none>:<none>'")?;
+        drop(script);
+        fs::set_permissions(&podman, fs::Permissions::from_mode(0o755))?;
+
+        let scanner = ContainerScanner {
+            search_path: bin.path().as_os_str().to_owned(),
+        };
+        assert_eq!(
+            find_engines(&scanner.search_path),
+            vec![(ContainerEngine::Podman, podman)]
+        );
+
+        let result = scanner.scan(None, &ScanOptions::default());
+        assert_eq!(result.items.len(), 1);
+        assert_eq!(
+            result.items[0].path,
+            PathBuf::from("podman://abc123/<none>:<none>")
+        );
+        assert_eq!(result.total_size, 1_000_000);
+
+        let options = ScanOptions {
+            no_external_commands: true,
+            ..ScanOptions::default()
+        };
+        assert!(scanner.scan(None, &options).items.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn engine_outcomes_map_to_status_labels() {
+        let output = |code, stdout: &str| {
+            Ok(Output {
+                status: ExitStatus::from_raw(code << 8),
+                stdout: stdout.as_bytes().to_vec(),
+                stderr: Vec::new(),
+            })
+        };
+        let missing = Err(io::Error::from(io::ErrorKind::NotFound));
+        let statuses = [
+            EngineStatus::from_output(&missing),
+            EngineStatus::from_output(&output(1, "")),
+            EngineStatus::from_output(&output(0, "")),
+            EngineStatus::from_output(&output(0, "abc123|1MB|<none>:<none>\n")),
+        ];
+        assert_eq!(
+            statuses,
+            [
+                EngineStatus::NotInstalled,
+                EngineStatus::DaemonDown,
+                EngineStatus::NothingToClean,
+                EngineStatus::Found(1),
+            ]
+        );
+        assert_eq!(
+            statuses.map(EngineStatus::label),
+            [
+                Some("not installed"),
+                Some("daemon not running"),
+                Some("no dangling images"),
+                None,
+            ]
+        );
+
+        assert_eq!(
+            status_note(&[]),
+            Some("docker and podman not installed".to_string())
+        );
+        assert_eq!(
+            status_note(&[
+                (ContainerEngine::Docker, EngineStatus::DaemonDown),
+                (ContainerEngine::Podman, EngineStatus::Found(2)),
+            ]),
+            Some("docker daemon not running".to_string())
+        );
+        assert_eq!(
+            status_note(&[(ContainerEngine::Docker, EngineStatus::Found(2))]),
+            None
+        );
+    }
+}
@@ -1,11 +1,9 @@
-use crate::allowlist::Allowlist;
 use crate::constants::{
-    BUN_CACHE, CARGO_REGISTRY, GO_MOD_CACHE, GRADLE_CACHE, NODE_MODULES, NPM_CACHE, PNPM_STORE,
-    PROJECTS_DIR,
+    BUN_CACHE, CARGO_REGISTRY, GO_MOD_CACHE, GRADLE_CACHE, NPM_CACHE, PNPM_STORE, PROJECTS_DIRS,
 };
-use crate::model::{CategoryType, ScanResult};
+use crate::model::{CategoryType, ScanResult, ScannedItem, common_ancestor};
 use crate::scanner::utils::scan_recursive_for_target;
-use crate::scanner::{PathScanner, Scanner};
+use crate::scanner::{PathScanner, ProgressFn, ScanOptions, Scanner};
 use std::path::{Path, PathBuf};
 
 pub fn developer_caches_scanner(home: &Path) -> PathScanner {
@@ -27,39 +25,167 @@ pub fn developer_caches_scanner(home: &Path) -> PathScanner {
 
     PathScanner {
         category: CategoryType::DeveloperCaches,
-        description: "Caches for npm, bun, pnpm, go, cargo, gradle, etc.".to_string(),
         paths,
+        flatten_files: false,
     }
 }
 
-pub struct NodeModulesScanner {
-    pub home: PathBuf,
+/// Returns the common project roots in `home` that exist, followed by `extra` roots.
+pub fn discover_project_roots(home: &Path, extra: &[PathBuf]) -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = PROJECTS_DIRS
+        .iter()
+        .map(|dir| home.join(dir))
+        .filter(|root| root.is_dir())
+        .collect();
+    for root in extra {
+        if !roots.contains(root) {
+            roots.push(root.clone());
+        }
+    }
+    roots
+}
+
+/// Recursively searches project roots for directories with any of the `targets` names.
+pub struct RecursiveTargetScanner {
+    pub category: CategoryType,
+    pub roots: Vec<PathBuf>,
+    pub targets: Vec<String>,
+    pub max_depth: usize,
 }
 
-impl Scanner for NodeModulesScanner {
+impl Scanner for RecursiveTargetScanner {
     fn category(&self) -> CategoryType {
-        CategoryType::NodeModules
+        self.category
     }
 
-    fn description(&self) -> String {
-        "Unused node_modules (Recursively found in ~/Projects)".to_string()
-    }
+    fn scan(&self, progress_cb: Option<&ProgressFn>, options: &ScanOptions) -> ScanResult {
+        let roots: Vec<PathBuf> = self
+            .roots
+            .iter()
+            .filter(|root| root.exists())
+            .cloned()
+            .collect();
+        let found: Vec<ScannedItem> = roots
+            .iter()
+            .flat_map(|root| {
+                self.targets.iter().flat_map(move |target| {
+                    scan_recursive_for_target(root, target, self.max_depth, progress_cb, options)
+                })
+            })
+            .collect();
 
-    fn scan(&self, progress_cb: Option<&(dyn Fn() + Sync)>, allowlist: &Allowlist) -> ScanResult {
-        let path = self.home.join(PROJECTS_DIR);
-        let items = if path.exists() {
-            scan_recursive_for_target(&path, NODE_MODULES, progress_cb, allowlist)
+        // Roots may overlap (~/Projects and ~/Projects/client), so the items are
+        // deduplicated as a whole rather than merged per root
+        let path = if roots.is_empty() {
+            self.roots.first().cloned().unwrap_or_default()
         } else {
-            vec![]
+            // With several roots (~/Projects and ~/dev) show where they meet, usually home
+            common_ancestor(&roots)
         };
-
         ScanResult {
-            category: self.category(),
-            total_size: items.iter().map(|i| i.size).sum(),
-            items,
-            is_selected: false,
-            description: self.description(),
-            root_path: path,
+            roots,
+            ..ScanResult::new(self.category(), path, remove_nested_items(found))
+        }
+    }
+}
+
+/// Drops items located inside another item (e.g. `target/debug/build` found by the
+/// `build` search within an already found `target`), so nothing is counted twice.
+fn remove_nested_items(mut items: Vec<ScannedItem>) -> Vec<ScannedItem> {
+    // Sorting by path places every ancestor directly before its descendants
+    items.sort_by(|a, b| a.path.cmp(&b.path));
+    let mut kept: Vec<ScannedItem> = Vec::with_capacity(items.len());
+    for item in items {
+        if kept
+            .last()
+            .is_none_or(|last| !item.path.starts_with(&last.path))
+        {
+            kept.push(item);
+        }
+    }
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::NODE_MODULES;
+    use anyhow::Result;
+    use std::fs::{self, File};
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    fn scanner(root: &Path, targets: &[&str]) -> RecursiveTargetScanner {
+        RecursiveTargetScanner {
+            category: CategoryType::BuildArtifacts,
+            roots: vec![root.to_path_buf()],
+            targets: targets.iter().map(ToString::to_string).collect(),
+            max_depth: 5,
         }
     }
+
+    #[test]
+    fn finds_multiple_target_kinds() -> Result<()> {
+        let dir = tempdir()?;
+        let root = dir.path();
+        fs::create_dir_all(root.join("web/node_modules"))?;
+        File::create(root.join("web/node_modules/lib.js"))?.write_all(&[0u8; 100])?;
+        fs::create_dir_all(root.join("cli/target/debug"))?;
+        File::create(root.join("cli/target/debug/cli"))?.write_all(&[0u8; 300])?;
+
+        let result = scanner(root, &[NODE_MODULES, "target"]).scan(None, &ScanOptions::default());
+
+        assert_eq!(result.items.len(), 2);
+        assert_eq!(result.items[0].path, root.join("cli/target"));
+        assert_eq!(result.items[1].path, root.join("web/node_modules"));
+        assert_eq!(result.total_size, 400);
+        Ok(())
+    }
+
+    #[test]
+    fn scans_every_discovered_root() -> Result<()> {
+        let home = tempdir()?;
+        let home = home.path();
+        for root in ["Projects/web", "dev/api"] {
+            fs::create_dir_all(home.join(root).join(NODE_MODULES))?;
+            File::create(home.join(root).join(NODE_MODULES).join("lib.js"))?
+                .write_all(&[0u8; 10])?;
+        }
+        let extra = home.join("clients");
+        fs::create_dir(&extra)?;
+
+        let roots = discover_project_roots(home, std::slice::from_ref(&extra));
+        assert_eq!(roots, vec![home.join("Projects"), home.join("dev"), extra]);
+
+        let result = RecursiveTargetScanner {
+            roots,
+            ..scanner(home, &[NODE_MODULES])
+        }
+        .scan(None, &ScanOptions::default());
+        assert_eq!(result.items.len(), 2);
+        assert_eq!(result.total_size, 20);
+        assert_eq!(result.root_path, home);
+        assert_eq!(result.roots.len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn finds_hidden_targets_without_double_counting() -> Result<()> {
+        let dir = tempdir()?;
+        let root = dir.path();
+        fs::create_dir_all(root.join("site/.next/cache"))?;
+        // `build` inside `target` belongs to the already found `target`
+        fs::create_dir_all(root.join("cli/target/debug/build"))?;
+        File::create(root.join("cli/target/debug/build/out"))?.write_all(&[0u8; 10])?;
+
+        let result =
+            scanner(root, &["target", ".next", "build"]).scan(None, &ScanOptions::default());
+
+        let paths: Vec<_> = result.items.iter().map(|i| i.path.clone()).collect();
+        assert_eq!(paths.len(), 2);
+        assert!(paths.contains(&root.join("site/.next")));
+        assert!(paths.contains(&root.join("cli/target")));
+        assert_eq!(result.total_size, 10);
+        Ok(())
+    }
 }
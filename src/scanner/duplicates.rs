@@ -0,0 +1,108 @@
+use crate::model::ScannedItem;
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::hash::{DefaultHasher, Hasher};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Groups files with identical contents, e.g. a `.dmg` in Downloads and its copy in the Trash.
+/// Only files of at least `min_size` bytes that share their size with another file are hashed.
+pub fn find_duplicates<'a>(
+    items: impl IntoIterator<Item = &'a ScannedItem>,
+    min_size: u64,
+) -> Vec<Vec<PathBuf>> {
+    let mut seen = HashSet::new();
+    let mut by_size: HashMap<u64, Vec<&Path>> = HashMap::new();
+    for item in items {
+        if item.size >= min_size && item.path.is_file() && seen.insert(item.path.as_path()) {
+            by_size.entry(item.size).or_default().push(&item.path);
+        }
+    }
+
+    let candidates: Vec<&Path> = by_size
+        .into_values()
+        .filter(|paths| paths.len() > 1)
+        .flatten()
+        .collect();
+    let hashed: Vec<((u64, u64), &Path)> = candidates
+        .par_iter()
+        .filter_map(|path| {
+            let size = path.metadata().ok()?.len();
+            let hash = content_hash(path)
+                .inspect_err(|e| log::warn!("Failed to hash {}: {e}", path.display()))
+                .ok()?;
+            Some(((size, hash), *path))
+        })
+        .collect();
+
+    let mut by_content: HashMap<(u64, u64), Vec<PathBuf>> = HashMap::new();
+    for (key, path) in hashed {
+        by_content.entry(key).or_default().push(path.to_path_buf());
+    }
+    let mut groups: Vec<Vec<PathBuf>> = by_content
+        .into_values()
+        .filter(|paths| paths.len() > 1)
+        .map(|mut paths| {
+            paths.sort();
+            paths
+        })
+        .collect();
+    groups.sort();
+    groups
+}
+
+/// Hashes the full contents of the file at `path`.
+fn content_hash(path: &Path) -> io::Result<u64> {
+    let mut file = File::open(path)?;
+    let mut hasher = DefaultHasher::new();
+    let mut buf = vec![0; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            return Ok(hasher.finish());
+        }
+        hasher.write(&buf[..n]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::SystemTime;
+
+    fn item(path: PathBuf) -> ScannedItem {
+        let size = path.metadata().unwrap().len();
+        ScannedItem {
+            path,
+            size,
+            modified: SystemTime::now(),
+        }
+    }
+
+    #[test]
+    fn groups_identical_files_only() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let write = |name: &str, content: &[u8]| -> anyhow::Result<PathBuf> {
+            let path = dir.path().join(name);
+            std::fs::write(&path, content)?;
+            Ok(path)
+        };
+        let download = write("Tool.dmg", b"installer")?;
+        let trashed = write("Tool copy.dmg", b"installer")?;
+        // Same size, different contents
+        let other = write("Other.dmg", b"different")?;
+        let small = write("a.txt", b"x")?;
+        let small_copy = write("b.txt", b"x")?;
+
+        let items: Vec<ScannedItem> = [&download, &trashed, &other, &small, &small_copy]
+            .into_iter()
+            .cloned()
+            .map(item)
+            .collect();
+        let groups = find_duplicates(&items, 2);
+
+        assert_eq!(groups, vec![vec![trashed, download]]);
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+use crate::scanner::ScanOptions;
+use crate::scanner::utils::{calculate_item_stats, is_offloaded};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// Why a path is left out of the scan (`--explain`).
+#[derive(Debug, PartialEq, Eq)]
+pub enum Exclusion {
+    /// The path doesn't exist.
+    Missing,
+    /// An allowlist rule covers the path.
+    Allowlisted(String),
+    /// The path (or, for a folder, its contents) can't be read.
+    Unreadable(io::ErrorKind),
+    /// Offloaded to iCloud, so scanning it would download it.
+    Offloaded,
+    /// Smaller than [`ScanOptions::min_size`].
+    TooSmall { size: u64, min_size: u64 },
+    /// Modified more recently than [`ScanOptions::min_age`].
+    TooNew { min_age: Duration },
+}
+
+impl fmt::Display for Exclusion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing => write!(f, "it doesn't exist"),
+            Self::Allowlisted(rule) => write!(f, "it matches the allowlist rule `{rule}`"),
+            Self::Unreadable(kind) => write!(f, "it can't be read ({kind})"),
+            Self::Offloaded => write!(f, "it is offloaded to iCloud"),
+            Self::TooSmall { size, min_size } => {
+                write!(f, "its {size} bytes are below the minimum of {min_size}")
+            }
+            Self::TooNew { min_age } => write!(
+                f,
+                "it was modified within the last {} seconds",
+                min_age.as_secs()
+            ),
+        }
+    }
+}
+
+/// Every reason `path` would be left out when scanning with `options`.
+/// Empty when nothing stops it from being scanned.
+pub fn explain(path: &Path, options: &ScanOptions) -> Vec<Exclusion> {
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return vec![Exclusion::Missing],
+        Err(e) => return vec![Exclusion::Unreadable(e.kind())],
+    };
+
+    let mut reasons = Vec::new();
+    if let Some(rule) = options.allowlist.excluding_rule(path) {
+        reasons.push(Exclusion::Allowlisted(rule.to_string()));
+    }
+    if metadata.is_dir()
+        && let Err(e) = fs::read_dir(path)
+    {
+        reasons.push(Exclusion::Unreadable(e.kind()));
+    }
+    if is_offloaded(&metadata) {
+        reasons.push(Exclusion::Offloaded);
+    }
+    if options.min_size > 0 || options.min_age.is_some() {
+        let item = calculate_item_stats(path, options);
+        if item.size < options.min_size {
+            reasons.push(Exclusion::TooSmall {
+                size: item.size,
+                min_size: options.min_size,
+            });
+        }
+        if let Some(min_age) = options.min_age
+            && item.is_recent(min_age, SystemTime::now())
+        {
+            reasons.push(Exclusion::TooNew { min_age });
+        }
+    }
+    reasons
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::allowlist::Allowlist;
+
+    #[test]
+    fn explains_allowlisted_and_filtered_paths() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let kept = dir.path().join("Keep");
+        fs::create_dir(&kept)?;
+        fs::write(kept.join("notes.txt"), b"hello")?;
+        let rule = kept.display().to_string();
+        let options = ScanOptions {
+            allowlist: Allowlist::new(vec![rule.clone()]),
+            ..ScanOptions::default()
+        };
+
+        assert_eq!(
+            explain(&kept.join("notes.txt"), &options),
+            vec![Exclusion::Allowlisted(rule)]
+        );
+        assert!(explain(dir.path(), &options).is_empty());
+        assert_eq!(
+            explain(&dir.path().join("gone"), &options),
+            vec![Exclusion::Missing]
+        );
+
+        let options = ScanOptions {
+            min_size: 1024,
+            min_age: Some(Duration::from_hours(1)),
+            ..ScanOptions::default()
+        };
+        assert_eq!(
+            explain(&kept.join("notes.txt"), &options),
+            vec![
+                Exclusion::TooSmall {
+                    size: 5,
+                    min_size: 1024
+                },
+                Exclusion::TooNew {
+                    min_age: Duration::from_hours(1)
+                },
+            ]
+        );
+        Ok(())
+    }
+}
@@ -1,26 +1,109 @@
 pub mod browsers;
+pub mod container;
 pub mod dev;
-pub mod docker;
+pub mod duplicates;
+pub mod explain;
+pub mod open_files;
+pub mod paths;
+pub mod quicklook;
 pub mod trash;
 pub mod user;
 pub mod utils;
 pub mod xcode;
 
 use crate::allowlist::Allowlist;
-use crate::model::{CategoryType, ScanResult};
-use crate::scanner::utils::scan_path;
-use std::path::PathBuf;
+use crate::constants::{BUILD_ARTIFACT_DIRS, NODE_MODULES, PROJECTS_MAX_DEPTH};
+use crate::model::{CategoryFilter, CategoryType, ProgressEvent, ScanResult};
+use crate::scanner::utils::{scan_files, scan_path};
+use anyhow::Result;
+use log::warn;
+use rayon::prelude::*;
+use std::cmp::Reverse;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Callback through which scanners report their progress.
+pub type ProgressFn = dyn Fn(ProgressEvent) + Sync;
+
+/// Settings shared by every scanner during a scan.
+#[derive(Default, Clone)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct ScanOptions {
+    pub allowlist: Allowlist,
+    /// Stay on the filesystem of the scanned root (like `du -x`).
+    pub one_file_system: bool,
+    /// Roots searched for `node_modules`. Defaults to the common roots that exist when empty.
+    pub project_roots: Vec<PathBuf>,
+    /// How deep to search below each project root.
+    pub max_depth: Option<usize>,
+    /// Directory names treated as build artifacts. Defaults to `BUILD_ARTIFACT_DIRS`.
+    pub artifact_dirs: Option<Vec<String>>,
+    /// Categories to scan (`--only` / `--exclude`).
+    pub categories: CategoryFilter,
+    /// Wall-clock time each category may take before it stops descending.
+    pub time_budget: Option<Duration>,
+    /// When the running category's budget is used up. Set by [`run_scanner`].
+    pub deadline: Option<Instant>,
+    /// Only touch the filesystem: skip scanners that run `docker`, `getconf`, etc.
+    pub no_external_commands: bool,
+    /// Set to stop every running scan, which then returns what it found so far.
+    pub cancelled: Arc<AtomicBool>,
+    /// How many threads to scan with (`--jobs`). Defaults to one per core.
+    pub jobs: Option<usize>,
+    /// Leave out items smaller than this many bytes.
+    pub min_size: u64,
+    /// Leave out items modified more recently than this.
+    pub min_age: Option<Duration>,
+    /// Where to look for screenshots, instead of where the Screenshot app saves them.
+    pub screenshot_location: Option<PathBuf>,
+    /// Leave caches that running apps have open unselected, found with `lsof`.
+    pub skip_open_files: bool,
+    /// List the files inside Downloads folders one by one instead of each folder as a whole.
+    pub flatten_downloads: bool,
+    /// Directories the running category couldn't read. Set by [`run_scanner`].
+    pub read_errors: Arc<Mutex<Vec<(PathBuf, io::ErrorKind)>>>,
+    /// Counts the iCloud-offloaded files the running category skipped. Set by [`run_scanner`].
+    pub offloaded: Arc<AtomicUsize>,
+}
+
+impl ScanOptions {
+    /// Whether the running category has used up its time budget.
+    #[must_use]
+    pub fn timed_out(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Whether scanning should stop, either out of time or cancelled.
+    #[must_use]
+    pub fn should_stop(&self) -> bool {
+        self.is_cancelled() || self.timed_out()
+    }
+}
 
 pub trait Scanner: Send + Sync {
     fn category(&self) -> CategoryType;
-    fn description(&self) -> String;
-    fn scan(&self, progress_cb: Option<&(dyn Fn() + Sync)>, allowlist: &Allowlist) -> ScanResult;
+    fn scan(&self, progress_cb: Option<&ProgressFn>, options: &ScanOptions) -> ScanResult;
+
+    /// Whether scanning spawns external commands rather than only reading files.
+    fn uses_external_commands(&self) -> bool {
+        false
+    }
 }
 
 pub struct PathScanner {
     pub category: CategoryType,
-    pub description: String,
     pub paths: Vec<PathBuf>,
+    /// List every file below the paths as its own item, instead of each top-level entry.
+    pub flatten_files: bool,
 }
 
 impl Scanner for PathScanner {
@@ -28,53 +111,79 @@ impl Scanner for PathScanner {
         self.category
     }
 
-    fn description(&self) -> String {
-        self.description.clone()
+    fn scan(&self, progress_cb: Option<&ProgressFn>, options: &ScanOptions) -> ScanResult {
+        let mut results = self.paths.iter().map(|path| {
+            let (_, items) = if self.flatten_files {
+                scan_files(path, progress_cb, options)
+            } else {
+                scan_path(path, progress_cb, options)
+            };
+            ScanResult::new(self.category, path.clone(), items)
+        });
+        // No paths at all: show the category as living in home
+        let Some(first) = results.next() else {
+            let home = crate::home::resolve_home().unwrap_or_else(|| PathBuf::from("/"));
+            return ScanResult {
+                roots: Vec::new(),
+                ..ScanResult::new(self.category, home, Vec::new())
+            };
+        };
+        let mut result = first.merge(results);
+        result.roots.retain(|root| root.exists());
+        result
     }
+}
 
-    fn scan(&self, progress_cb: Option<&(dyn Fn() + Sync)>, allowlist: &Allowlist) -> ScanResult {
-        let mut all_items = Vec::new();
-
-        for path in &self.paths {
-            let (_, mut items) = scan_path(path, progress_cb, allowlist);
-            all_items.append(&mut items);
-        }
-
-        let total_size: u64 = all_items.iter().map(|i| i.size).sum();
-
-        // Determine a "root" path for display.
-        // If there is only one path, use it. If multiple, maybe just the parent dir of the first or common path?
-        // OR just the first path.
-        let root_path = if self.paths.is_empty() {
-            dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"))
-        } else {
-            self.paths[0].clone()
-        };
+pub fn get_all_scanners(options: &ScanOptions) -> Result<Vec<Box<dyn Scanner>>> {
+    scanners_for_home(crate::home::resolve_home(), options)
+}
 
-        ScanResult {
-            category: self.category,
-            total_size,
-            items: all_items,
-            is_selected: false,
-            description: self.description.clone(),
-            root_path,
-        }
-    }
+/// Builds the scanner for a single category, if it is scanned at all.
+pub fn scanner_for(
+    category: CategoryType,
+    options: &ScanOptions,
+) -> Result<Option<Box<dyn Scanner>>> {
+    Ok(get_all_scanners(options)?
+        .into_iter()
+        .find(|scanner| scanner.category() == category))
 }
 
-pub fn get_all_scanners() -> Vec<Box<dyn Scanner>> {
-    let home = dirs::home_dir().expect("Home directory not found");
+/// Builds the scanners for `home`, failing if it couldn't be determined.
+fn scanners_for_home(
+    home: Option<PathBuf>,
+    options: &ScanOptions,
+) -> Result<Vec<Box<dyn Scanner>>> {
+    let home = crate::home::require_home(home)?;
+    let project_roots = if options.project_roots.is_empty() {
+        dev::discover_project_roots(&home, &[])
+    } else {
+        options.project_roots.clone()
+    };
+    let max_depth = options.max_depth.unwrap_or(PROJECTS_MAX_DEPTH);
+    let artifact_dirs = options.artifact_dirs.clone().unwrap_or_else(|| {
+        BUILD_ARTIFACT_DIRS
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    });
+
+    // Scanners are filtered by category below, but some constructors run `defaults`
+    // or `getconf`, which only categories taking part in the scan should pay for
+    let may_run = |category| !options.no_external_commands && options.categories.includes(category);
 
-    vec![
+    let scanners: Vec<Box<dyn Scanner>> = vec![
         // Xcode: DerivedData, Archives, DeviceSupport
-        Box::new(xcode::xcode_scanner(&home)),
+        Box::new(xcode::xcode_scanner(
+            &home,
+            may_run(CategoryType::XcodeJunk),
+        )),
         // System Logs: /Library/Logs, /private/var/log
         Box::new(user::system_logs_scanner()),
         // System Cache: /Library/Caches
         Box::new(PathScanner {
             category: CategoryType::SystemCache,
-            description: "System cache files.".to_string(),
             paths: vec![PathBuf::from(crate::constants::SYSTEM_LIBRARY_CACHES)],
+            flatten_files: false,
         }),
         // User Logs: ~/Library/Logs
         Box::new(user::user_logs_scanner(&home)),
@@ -85,18 +194,436 @@ pub fn get_all_scanners() -> Vec<Box<dyn Scanner>> {
         // Downloads: ~/Downloads
         Box::new(PathScanner {
             category: CategoryType::Downloads,
-            description: "All files in Downloads folder.".to_string(),
             paths: vec![home.join(crate::constants::DOWNLOADS_DIR)],
+            flatten_files: options.flatten_downloads,
         }),
         // Trash: ~/.Trash
         Box::new(trash::trash_scanner(&home)),
         // Developer Caches: .npm, .cargo, etc.
         Box::new(dev::developer_caches_scanner(&home)),
-        // Screen Capture: Desktop screenshots
-        Box::new(user::ScreenCaptureScanner { home: home.clone() }),
-        // Node Modules: Recursive search in ~/Projects
-        Box::new(dev::NodeModulesScanner { home: home.clone() }),
-        // Docker: dangling images
-        Box::new(docker::DockerScanner),
-    ]
+        // Screen Capture: screenshots and recordings where the Screenshot app saves them
+        Box::new(user::screen_capture_scanner(
+            &home,
+            options.screenshot_location.as_deref(),
+            may_run(CategoryType::ScreenCapture),
+        )),
+        // Node Modules: Recursive search in the project roots (~/Projects, ~/dev, ... by default)
+        Box::new(dev::RecursiveTargetScanner {
+            category: CategoryType::NodeModules,
+            roots: project_roots.clone(),
+            targets: vec![NODE_MODULES.to_string()],
+            max_depth,
+        }),
+        // Build Artifacts: target/, .next/, dist/, ... in the project roots
+        Box::new(dev::RecursiveTargetScanner {
+            category: CategoryType::BuildArtifacts,
+            roots: project_roots,
+            targets: artifact_dirs,
+            max_depth,
+        }),
+        // QuickLook: thumbnail caches in /private/var/folders and ~/Library/Caches
+        Box::new(quicklook::quicklook_scanner(
+            &home,
+            may_run(CategoryType::QuickLookCache),
+        )),
+        // Containers: dangling Docker/Podman images
+        Box::new(container::ContainerScanner::from_env()),
+    ];
+
+    let mut scanners: Vec<Box<dyn Scanner>> = scanners
+        .into_iter()
+        .filter(|scanner| options.categories.includes(scanner.category()))
+        .filter(|scanner| !(options.no_external_commands && scanner.uses_external_commands()))
+        .collect();
+    scanners.sort_by_key(|scanner| scanner.category().order());
+    Ok(scanners)
+}
+
+/// Runs `scanner` within the per-category time budget.
+/// Results cut short by the budget are flagged as timed out.
+pub fn run_scanner(
+    scanner: &dyn Scanner,
+    progress_cb: Option<&ProgressFn>,
+    options: &ScanOptions,
+) -> ScanResult {
+    let options = ScanOptions {
+        deadline: options.time_budget.map(|budget| Instant::now() + budget),
+        offloaded: Arc::default(),
+        read_errors: Arc::default(),
+        ..options.clone()
+    };
+    let started = Instant::now();
+    let mut result = scanner.scan(progress_cb, &options);
+    result.timed_out = options.timed_out();
+    result.offloaded = options.offloaded.load(Ordering::Relaxed);
+    if let Ok(errors) = options.read_errors.lock() {
+        result.warnings = utils::read_warnings(&errors);
+    }
+    if options.skip_open_files
+        && !options.no_external_commands
+        && open_files::applies_to(result.category)
+    {
+        result.in_use = open_files::items_in_use(&result.items);
+    }
+    if result.offloaded > 0 {
+        warn!(
+            "Skipped {} iCloud-offloaded files in {}",
+            result.offloaded,
+            result.category.name()
+        );
+    }
+    if options.min_size > 0 || options.min_age.is_some() {
+        let now = SystemTime::now();
+        result.items.retain(|item| {
+            item.size >= options.min_size
+                && options.min_age.is_none_or(|age| !item.is_recent(age, now))
+        });
+        result.total_size = result.items.iter().map(|i| i.size).sum();
+    }
+    result.duration = started.elapsed();
+    result
+}
+
+/// Builder for the global thread pool, capped at `jobs` threads when given.
+pub fn thread_pool_builder(jobs: Option<usize>) -> rayon::ThreadPoolBuilder {
+    rayon::ThreadPoolBuilder::new().num_threads(jobs.unwrap_or(0))
+}
+
+/// Runs every scanner to completion without progress reporting,
+/// returning the results sorted by size (largest first).
+///
+/// # Errors
+///
+/// Fails when the home directory can't be determined.
+pub fn scan_all(options: &ScanOptions) -> Result<Vec<ScanResult>> {
+    Ok(run_all(&get_all_scanners(options)?, options))
+}
+
+/// Like [`scan_all`], but for the user whose home directory is `home`.
+///
+/// # Errors
+///
+/// Never fails at the moment; the `Result` keeps it interchangeable with [`scan_all`].
+pub fn scan_home(home: &Path, options: &ScanOptions) -> Result<Vec<ScanResult>> {
+    Ok(run_all(
+        &scanners_for_home(Some(home.to_path_buf()), options)?,
+        options,
+    ))
+}
+
+fn run_all(scanners: &[Box<dyn Scanner>], options: &ScanOptions) -> Vec<ScanResult> {
+    let mut results: Vec<ScanResult> = scanners
+        .par_iter()
+        .map(|scanner| run_scanner(scanner.as_ref(), None, options))
+        .collect();
+    results.sort_by_key(|r| Reverse(r.total_size));
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ItemKind;
+    use std::collections::{HashMap, HashSet};
+
+    fn test_home() -> PathBuf {
+        PathBuf::from("/Users/test")
+    }
+
+    #[test]
+    fn missing_home_is_an_error() {
+        let err = scanners_for_home(None, &ScanOptions::default())
+            .err()
+            .expect("scanners need a home directory");
+        assert!(err.to_string().contains("set $HOME"));
+    }
+
+    #[test]
+    fn scanners_follow_category_filter() {
+        let options = ScanOptions {
+            categories: CategoryFilter::Only(HashSet::from([
+                CategoryType::NodeModules,
+                CategoryType::DockerImages,
+            ])),
+            ..ScanOptions::default()
+        };
+        let categories: Vec<CategoryType> = scanners_for_home(Some(test_home()), &options)
+            .unwrap()
+            .iter()
+            .map(|s| s.category())
+            .collect();
+        assert_eq!(
+            categories,
+            vec![CategoryType::NodeModules, CategoryType::DockerImages]
+        );
+
+        let options = ScanOptions {
+            categories: CategoryFilter::Exclude(HashSet::from([CategoryType::Downloads])),
+            ..ScanOptions::default()
+        };
+        let scanners = scanners_for_home(Some(test_home()), &options).unwrap();
+        assert!(
+            scanners
+                .iter()
+                .all(|s| s.category() != CategoryType::Downloads)
+        );
+        assert_eq!(
+            scanners.len(),
+            scanners_for_home(Some(test_home()), &ScanOptions::default())
+                .unwrap()
+                .len()
+                - 1
+        );
+    }
+
+    #[test]
+    fn every_category_has_a_scanner() {
+        let categories: Vec<CategoryType> =
+            scanners_for_home(Some(test_home()), &ScanOptions::default())
+                .unwrap()
+                .iter()
+                .map(|s| s.category())
+                .collect();
+        // Sorted by order, so this also catches a category scanned twice
+        assert_eq!(categories, CategoryType::all());
+    }
+
+    #[test]
+    fn safe_mode_skips_command_scanners() {
+        let options = ScanOptions {
+            no_external_commands: true,
+            ..ScanOptions::default()
+        };
+        let scanners = scanners_for_home(Some(test_home()), &options).unwrap();
+        assert!(scanners.iter().all(|s| !s.uses_external_commands()));
+        assert!(
+            scanners
+                .iter()
+                .all(|s| s.category() != CategoryType::DockerImages)
+        );
+
+        let all = scanners_for_home(Some(test_home()), &ScanOptions::default()).unwrap();
+        assert!(all.iter().any(|s| s.uses_external_commands()));
+    }
+
+    #[test]
+    fn downloads_tags_installers() -> anyhow::Result<()> {
+        let home = tempfile::tempdir()?;
+        let downloads = home.path().join(crate::constants::DOWNLOADS_DIR);
+        std::fs::create_dir(&downloads)?;
+        for name in ["Tool.dmg", "Driver.PKG", "notes.txt"] {
+            std::fs::write(downloads.join(name), b"x")?;
+        }
+
+        let scanner = PathScanner {
+            category: CategoryType::Downloads,
+            paths: vec![downloads],
+            flatten_files: false,
+        };
+        let result = scanner.scan(None, &ScanOptions::default());
+
+        let mut installers: Vec<String> = result
+            .items
+            .iter()
+            .filter(|item| item.kind() == ItemKind::Installer)
+            .map(|item| {
+                item.path
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect();
+        installers.sort();
+        assert_eq!(installers, vec!["Driver.PKG", "Tool.dmg"]);
+        assert_eq!(result.items.len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn flattened_downloads_list_leaf_files() -> anyhow::Result<()> {
+        let home = tempfile::tempdir()?;
+        let downloads = home.path().join(crate::constants::DOWNLOADS_DIR);
+        std::fs::create_dir_all(downloads.join("Album/Disc 1"))?;
+        std::fs::create_dir(downloads.join("Empty"))?;
+        std::fs::create_dir_all(downloads.join("Apps/Tool.app/Contents"))?;
+        for name in [
+            "Tool.dmg",
+            "Album/cover.jpg",
+            "Album/Disc 1/01.mp3",
+            "Apps/Tool.app/Contents/Info.plist",
+        ] {
+            std::fs::write(downloads.join(name), b"x")?;
+        }
+        let names = |result: &ScanResult| {
+            let mut names: Vec<PathBuf> = result
+                .items
+                .iter()
+                .map(|item| item.path.strip_prefix(&downloads).unwrap().to_path_buf())
+                .collect();
+            names.sort();
+            names
+        };
+
+        let mut scanner = PathScanner {
+            category: CategoryType::Downloads,
+            paths: vec![downloads.clone()],
+            flatten_files: false,
+        };
+        let result = scanner.scan(None, &ScanOptions::default());
+        assert_eq!(
+            names(&result),
+            vec![
+                PathBuf::from("Album"),
+                PathBuf::from("Apps"),
+                PathBuf::from("Empty"),
+                PathBuf::from("Tool.dmg")
+            ]
+        );
+
+        scanner.flatten_files = true;
+        let result = scanner.scan(None, &ScanOptions::default());
+        assert_eq!(
+            names(&result),
+            vec![
+                PathBuf::from("Album/Disc 1/01.mp3"),
+                PathBuf::from("Album/cover.jpg"),
+                PathBuf::from("Apps/Tool.app"),
+                PathBuf::from("Tool.dmg")
+            ]
+        );
+        assert_eq!(result.total_size, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn path_scanner_keeps_its_main_folder_and_existing_roots() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let logs = dir.path().join("Logs");
+        let var_log = dir.path().join("var/log");
+        std::fs::create_dir_all(&var_log)?;
+        std::fs::write(var_log.join("system.log"), b"x")?;
+
+        let scanner = PathScanner {
+            category: CategoryType::SystemLogs,
+            paths: vec![logs.clone(), var_log.clone()],
+            flatten_files: false,
+        };
+        let result = scanner.scan(None, &ScanOptions::default());
+        assert_eq!(result.root_path, logs);
+        assert_eq!(result.roots, vec![var_log]);
+        assert_eq!(result.items.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn thread_pool_respects_jobs() -> anyhow::Result<()> {
+        let pool = thread_pool_builder(Some(1)).build()?;
+        assert_eq!(pool.current_num_threads(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn headless_scan_honors_the_allowlist() -> anyhow::Result<()> {
+        const SECRET_SIZE: usize = 77_777;
+        let home = tempfile::tempdir()?;
+        let home = home.path();
+        let write = |path: &str, size: usize| -> anyhow::Result<()> {
+            let path = home.join(path);
+            std::fs::create_dir_all(path.parent().unwrap())?;
+            std::fs::write(path, vec![0u8; size])?;
+            Ok(())
+        };
+        write("Downloads/Secret/plans.pdf", SECRET_SIZE)?;
+        write("Downloads/old.dmg", 100)?;
+        write("Library/Caches/com.secret.app/db", SECRET_SIZE)?;
+        write("Library/Caches/com.example.app/db", 10)?;
+        write("Projects/secret-app/node_modules/x/index.js", SECRET_SIZE)?;
+        write("Projects/app/node_modules/x/index.js", 1)?;
+
+        let options = ScanOptions {
+            allowlist: Allowlist::new(
+                [
+                    "Downloads/Secret",
+                    "Library/Caches/com.secret.app",
+                    "Projects/secret-app",
+                ]
+                .iter()
+                .map(|rule| home.join(rule).display().to_string())
+                .collect(),
+            ),
+            categories: CategoryFilter::Only(HashSet::from([
+                CategoryType::Downloads,
+                CategoryType::UserCache,
+                CategoryType::NodeModules,
+            ])),
+            ..ScanOptions::default()
+        };
+        let results = scan_home(home, &options)?;
+
+        let totals: HashMap<CategoryType, u64> =
+            results.iter().map(|r| (r.category, r.total_size)).collect();
+        assert_eq!(
+            totals,
+            HashMap::from([
+                (CategoryType::Downloads, 100),
+                (CategoryType::UserCache, 10),
+                (CategoryType::NodeModules, 1),
+            ])
+        );
+        assert!(
+            results
+                .iter()
+                .flat_map(|r| &r.items)
+                .all(|i| !i.path.to_string_lossy().contains("ecret"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn time_budget_returns_partial_results() -> anyhow::Result<()> {
+        let root = tempfile::tempdir()?;
+        let tree = root.path().join("tree");
+        for i in 0..50 {
+            let dir = tree.join(format!("dir{i}"));
+            std::fs::create_dir_all(&dir)?;
+            for j in 0..20 {
+                std::fs::write(dir.join(format!("file{j}")), [0u8; 10])?;
+            }
+        }
+
+        let scanner = PathScanner {
+            category: CategoryType::UserCache,
+            paths: vec![root.path().to_path_buf()],
+            flatten_files: false,
+        };
+        let full = run_scanner(&scanner, None, &ScanOptions::default());
+        assert!(!full.timed_out);
+        assert_eq!(full.total_size, 50 * 20 * 10);
+
+        let options = ScanOptions {
+            time_budget: Some(Duration::ZERO),
+            ..ScanOptions::default()
+        };
+        let partial = run_scanner(&scanner, None, &options);
+        assert!(partial.timed_out);
+        assert!(partial.total_size < full.total_size);
+        Ok(())
+    }
+
+    #[test]
+    fn run_scanner_records_duration() -> anyhow::Result<()> {
+        let root = tempfile::tempdir()?;
+        std::fs::write(root.path().join("file"), [0u8; 10])?;
+        let scanner = PathScanner {
+            category: CategoryType::UserCache,
+            paths: vec![root.path().to_path_buf()],
+            flatten_files: false,
+        };
+
+        let result = run_scanner(&scanner, None, &ScanOptions::default());
+        assert_eq!(result.total_size, 10);
+        assert!(result.duration > Duration::ZERO);
+        Ok(())
+    }
 }
@@ -0,0 +1,83 @@
+use crate::model::{CategoryType, ScannedItem};
+use log::{debug, warn};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Whether running apps keep files of `category` open, so deleting them could crash an app.
+pub fn applies_to(category: CategoryType) -> bool {
+    matches!(
+        category,
+        CategoryType::UserCache | CategoryType::BrowserCache | CategoryType::SystemCache
+    )
+}
+
+/// The items that hold a file some running process has open.
+pub fn items_in_use(items: &[ScannedItem]) -> Vec<PathBuf> {
+    // The folders the items are in, so `lsof` runs once per folder rather than per item
+    let dirs: BTreeSet<&Path> = items.iter().filter_map(|i| i.path.parent()).collect();
+    let open: Vec<PathBuf> = dirs.into_iter().flat_map(open_paths_under).collect();
+    items
+        .iter()
+        .filter(|item| open.iter().any(|path| path.starts_with(&item.path)))
+        .map(|item| item.path.clone())
+        .collect()
+}
+
+/// Runs `lsof +D <dir>` for the files open anywhere below `dir`.
+fn open_paths_under(dir: &Path) -> Vec<PathBuf> {
+    debug!("Looking for open files in {}", dir.display());
+    // `-F n` prints one field per line, so names with spaces parse reliably
+    match Command::new("lsof")
+        .args(["-F", "n", "+D"])
+        .arg(dir)
+        .output()
+    {
+        // lsof exits with 1 when nothing is open
+        Ok(out) => parse_lsof_names(&String::from_utf8_lossy(&out.stdout)),
+        Err(e) => {
+            warn!("Failed to execute lsof: {e}");
+            Vec::new()
+        }
+    }
+}
+
+/// Parses the file names (`n` lines) of `lsof -F n` output.
+fn parse_lsof_names(output: &str) -> Vec<PathBuf> {
+    output
+        .lines()
+        .filter_map(|line| line.strip_prefix('n'))
+        .filter(|name| name.starts_with('/'))
+        .map(PathBuf::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_lsof_field_output() {
+        let output = "\
+p412
+fcwd
+n/
+f12
+n/Users/test/Library/Caches/com.google.Chrome/Cache Data/index
+p980
+f3
+n/Users/test/Library/Caches/com.spotify.client/Data/db.sqlite
+f4
+nlocalhost:49152->localhost:5432
+";
+        assert_eq!(
+            parse_lsof_names(output),
+            vec![
+                PathBuf::from("/"),
+                PathBuf::from("/Users/test/Library/Caches/com.google.Chrome/Cache Data/index"),
+                PathBuf::from("/Users/test/Library/Caches/com.spotify.client/Data/db.sqlite"),
+            ]
+        );
+        assert!(parse_lsof_names("").is_empty());
+    }
+}
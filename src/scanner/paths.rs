@@ -0,0 +1,150 @@
+use crate::model::ScannedItem;
+use crate::scanner::ScanOptions;
+use crate::scanner::utils::calculate_item_stats;
+use log::warn;
+use rayon::prelude::*;
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::io::{self, BufRead};
+use std::os::unix::ffi::OsStringExt;
+use std::path::{Path, PathBuf};
+
+/// Reads newline-separated paths (e.g. the output of `find`) into items to clean.
+/// Relative paths are resolved against the current directory before the allowlist
+/// is checked. Missing and allowlisted paths, duplicates, `.`-style references and
+/// `home` or any folder above it are skipped with a warning.
+pub fn read_path_list(
+    reader: impl BufRead,
+    home: &Path,
+    options: &ScanOptions,
+) -> Vec<ScannedItem> {
+    let mut seen = HashSet::new();
+    let mut paths = Vec::new();
+    for line in reader.split(b'\n') {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => {
+                warn!("Stopped reading paths: {e}");
+                break;
+            }
+        };
+        let line = line.trim_ascii();
+        if line.is_empty() {
+            continue;
+        }
+        let path = PathBuf::from(OsString::from_vec(line.to_vec()));
+        let resolved = match resolve(&path) {
+            Ok(resolved) => resolved,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                warn!("{} doesn't exist", path.display());
+                continue;
+            }
+            Err(e) => {
+                warn!("Skipping {}: {e}", path.display());
+                continue;
+            }
+        };
+        if path.file_name().is_none() || home.starts_with(&resolved) {
+            warn!("Refusing to clean {}", path.display());
+            continue;
+        }
+        if !options.allowlist.is_allowed(&resolved) && seen.insert(resolved.clone()) {
+            paths.push(resolved);
+        }
+    }
+
+    paths
+        .par_iter()
+        .map(|path| calculate_item_stats(path, options))
+        .collect()
+}
+
+/// The absolute path `path` names, with the folders above it resolved.
+/// A link itself is kept rather than followed, as cleaning it removes the link.
+fn resolve(path: &Path) -> io::Result<PathBuf> {
+    path.symlink_metadata()?;
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => {
+            let parent = if parent.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                parent
+            };
+            Ok(parent.canonicalize()?.join(name))
+        }
+        _ => path.canonicalize(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::allowlist::Allowlist;
+    use std::fs::{self, File};
+    use std::io::BufReader;
+
+    #[test]
+    fn reads_existing_paths() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        // Paths come back resolved, e.g. /var as /private/var on macOS
+        let dir = tmp.path().canonicalize()?;
+        let cache = dir.join("cache");
+        fs::create_dir(&cache)?;
+        fs::write(cache.join("a.bin"), [0; 10])?;
+        fs::write(cache.join("b.bin"), [0; 5])?;
+        let log = dir.join("old.log");
+        fs::write(&log, [0; 3])?;
+        let kept = dir.join("kept.log");
+        fs::write(&kept, [0; 7])?;
+
+        let list = dir.join("list.txt");
+        let lines = [
+            cache.display().to_string(),
+            String::new(),
+            format!("  {}  ", log.display()),
+            log.display().to_string(),
+            dir.join("missing").display().to_string(),
+            kept.display().to_string(),
+            "/".to_string(),
+            ".".to_string(),
+            dir.join("cache/..").display().to_string(),
+            dir.display().to_string(),
+        ];
+        fs::write(&list, lines.join("\n"))?;
+
+        let options = ScanOptions {
+            allowlist: Allowlist::new(vec![kept.display().to_string()]),
+            ..ScanOptions::default()
+        };
+        let home = dir.join("home");
+        let items = read_path_list(BufReader::new(File::open(&list)?), &home, &options);
+
+        let found: Vec<(PathBuf, u64)> = items.into_iter().map(|i| (i.path, i.size)).collect();
+        assert_eq!(found, vec![(cache, 15), (log, 3)]);
+        Ok(())
+    }
+
+    #[test]
+    fn resolves_paths_before_the_allowlist() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let root = dir.path().canonicalize()?;
+        let secrets = root.join("Secrets");
+        fs::create_dir(&secrets)?;
+        let junk = root.join("junk.log");
+        fs::write(&junk, [0; 4])?;
+        let options = ScanOptions {
+            allowlist: Allowlist::new(vec![secrets.display().to_string()]),
+            ..ScanOptions::default()
+        };
+
+        let mut input = format!("{}/Secrets/../Secrets\n", root.display()).into_bytes();
+        // A line that isn't UTF-8 doesn't stop the rest from being read
+        input.extend(b"\xff\xfe\n");
+        input.extend(junk.display().to_string().as_bytes());
+
+        let items = read_path_list(input.as_slice(), Path::new("/nonexistent"), &options);
+        let found: Vec<PathBuf> = items.into_iter().map(|i| i.path).collect();
+        assert_eq!(found, vec![junk]);
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+use crate::constants::{LIBRARY_CACHES, QUICKLOOK_THUMBNAIL_CACHE};
+use crate::model::CategoryType;
+use crate::scanner::PathScanner;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Without `run_getconf` only the cache in `~/Library/Caches` is scanned.
+pub fn quicklook_scanner(home: &Path, run_getconf: bool) -> PathScanner {
+    let mut paths = Vec::new();
+    // The system-managed cache lives under /private/var/folders/<obfuscated>/C
+    if let Some(cache_dir) = run_getconf.then(darwin_user_cache_dir).flatten() {
+        paths.push(cache_dir.join(QUICKLOOK_THUMBNAIL_CACHE));
+    }
+    paths.push(home.join(LIBRARY_CACHES).join(QUICKLOOK_THUMBNAIL_CACHE));
+    paths.retain(|p| p.exists());
+
+    PathScanner {
+        category: CategoryType::QuickLookCache,
+        paths,
+        flatten_files: false,
+    }
+}
+
+/// Resolves the per-user cache directory in `/private/var/folders`.
+fn darwin_user_cache_dir() -> Option<PathBuf> {
+    let output = Command::new("getconf")
+        .arg("DARWIN_USER_CACHE_DIR")
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_getconf_output(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn parse_getconf_output(output: &str) -> Option<PathBuf> {
+    let path = output.trim();
+    if path.is_empty() || path == "undefined" {
+        None
+    } else {
+        Some(PathBuf::from(path))
+    }
+}
+
+/// Runs `qlmanage -r cache` so `quicklookd` drops its in-memory
+/// state after the cache files were removed.
+pub fn reset_quicklook_cache() {
+    match Command::new("qlmanage").args(["-r", "cache"]).output() {
+        Ok(out) if out.status.success() => log::info!("Reset the QuickLook cache"),
+        Ok(out) => log::warn!(
+            "qlmanage -r cache failed: {}",
+            String::from_utf8_lossy(&out.stderr).trim()
+        ),
+        Err(e) => log::warn!("Failed to execute qlmanage: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_darwin_user_cache_dir() {
+        assert_eq!(
+            parse_getconf_output("/var/folders/zz/zyxvpxvq6csfxvn_n0000000000000/C/\n"),
+            Some(PathBuf::from(
+                "/var/folders/zz/zyxvpxvq6csfxvn_n0000000000000/C/"
+            ))
+        );
+        assert_eq!(parse_getconf_output(""), None);
+        assert_eq!(parse_getconf_output("undefined\n"), None);
+    }
+}
@@ -7,7 +7,7 @@ pub fn trash_scanner(home: &Path) -> PathScanner {
     let path = home.join(TRASH_DIR);
     PathScanner {
         category: CategoryType::Trash,
-        description: "Trash folder contents.".to_string(),
         paths: vec![path],
+        flatten_files: false,
     }
 }
@@ -1,11 +1,10 @@
-use crate::allowlist::Allowlist;
 use crate::constants::{
-    FIREFOX_CACHE, GOOGLE_CHROME_CACHE, LIBRARY_CACHES, LIBRARY_LOGS, SAFARI_CACHE,
-    SYSTEM_LIBRARY_LOGS, VAR_LOG,
+    DESKTOP_DIR, FIREFOX_CACHE, GOOGLE_CHROME_CACHE, LIBRARY_CACHES, LIBRARY_LOGS, SAFARI_CACHE,
+    SCREEN_CAPTURE_EXTENSIONS, SCREEN_CAPTURE_PREFIXES, SYSTEM_LIBRARY_LOGS, VAR_LOG,
 };
-use crate::model::{CategoryType, ScanResult, ScannedItem};
-use crate::scanner::utils::scan_path;
-use crate::scanner::{PathScanner, Scanner};
+use crate::model::{CategoryType, ScanResult};
+use crate::scanner::utils::{read_defaults_path, scan_path};
+use crate::scanner::{PathScanner, ProgressFn, ScanOptions, Scanner};
 use rayon::prelude::*;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -23,8 +22,8 @@ pub fn system_logs_scanner() -> PathScanner {
 
     PathScanner {
         category: CategoryType::SystemLogs,
-        description: "System log files (/Library/Logs, /private/var/log).".to_string(),
         paths,
+        flatten_files: false,
     }
 }
 
@@ -32,8 +31,8 @@ pub fn user_logs_scanner(home: &Path) -> PathScanner {
     let path = home.join(LIBRARY_LOGS);
     PathScanner {
         category: CategoryType::UserLogs,
-        description: "User log files.".to_string(),
         paths: vec![path],
+        flatten_files: false,
     }
 }
 
@@ -46,13 +45,9 @@ impl Scanner for UserCacheScanner {
         CategoryType::UserCache
     }
 
-    fn description(&self) -> String {
-        "User cache files (including sandboxed apps).".to_string()
-    }
-
-    fn scan(&self, progress_cb: Option<&(dyn Fn() + Sync)>, allowlist: &Allowlist) -> ScanResult {
+    fn scan(&self, progress_cb: Option<&ProgressFn>, options: &ScanOptions) -> ScanResult {
         let path = self.home.join(LIBRARY_CACHES);
-        let (_, mut items) = scan_path(&path, progress_cb, allowlist);
+        let (_, mut items) = scan_path(&path, progress_cb, options);
 
         // Filter out standard browser caches from standard user cache
         items.retain(|item| {
@@ -62,43 +57,72 @@ impl Scanner for UserCacheScanner {
                 && !p.to_string_lossy().contains(FIREFOX_CACHE)
         });
 
+        let caches = ScanResult::new(self.category(), path, items);
+
         // Scan ~/Library/Containers/*/Data/Library/Caches
         let containers_path = self.home.join("Library/Containers");
-        if containers_path.exists()
-            && let Ok(entries) = fs::read_dir(&containers_path)
-        {
-            let container_caches: Vec<PathBuf> = entries
-                .filter_map(Result::ok)
-                .map(|e| e.path().join("Data/Library/Caches"))
-                .filter(|p| p.exists())
-                .collect();
-
-            // Scan each found container cache
-            // Parallelize scanning across different path roots
-            let container_items: Vec<ScannedItem> = container_caches
-                .par_iter()
-                .flat_map(|path| {
-                    let (_, items) = scan_path(path, progress_cb, allowlist);
-                    items
-                })
-                .collect();
-
-            items.extend(container_items);
-        }
-
-        ScanResult {
-            category: self.category(),
-            total_size: items.iter().map(|i| i.size).sum(),
-            items,
-            is_selected: false,
-            description: self.description(),
-            root_path: path,
-        }
+        let container_caches: Vec<PathBuf> = fs::read_dir(&containers_path)
+            .into_iter()
+            .flatten()
+            .filter_map(Result::ok)
+            .map(|e| e.path().join("Data/Library/Caches"))
+            .filter(|p| p.exists())
+            .collect();
+
+        // Parallelize scanning across different path roots
+        let container_results: Vec<ScanResult> = container_caches
+            .into_par_iter()
+            .map(|path| {
+                let (_, items) = scan_path(&path, progress_cb, options);
+                ScanResult::new(self.category(), path, items)
+            })
+            .collect();
+
+        let mut result = caches.merge(container_results);
+        result.roots.retain(|root| root.exists());
+        result
     }
 }
 
 pub struct ScreenCaptureScanner {
-    pub home: PathBuf,
+    /// Where screenshots are saved: the Desktop unless moved in the Screenshot app.
+    pub location: PathBuf,
+}
+
+/// Looks for screenshots in the `configured` location, or else where the Screenshot
+/// app saves them. Without `run_defaults` the app's setting isn't read.
+pub fn screen_capture_scanner(
+    home: &Path,
+    configured: Option<&Path>,
+    run_defaults: bool,
+) -> ScreenCaptureScanner {
+    let location = match configured {
+        Some(configured) => Some(configured.to_path_buf()),
+        None if run_defaults => read_defaults_path("com.apple.screencapture", "location", home),
+        None => None,
+    };
+    ScreenCaptureScanner {
+        location: screen_capture_location(home, location),
+    }
+}
+
+/// The screenshot folder to scan: `location` if it exists, or else the Desktop.
+/// A folder that was deleted after being chosen makes macOS save to the Desktop too.
+fn screen_capture_location(home: &Path, location: Option<PathBuf>) -> PathBuf {
+    location
+        .filter(|location| location.is_dir())
+        .unwrap_or_else(|| home.join(DESKTOP_DIR))
+}
+
+/// Whether a file name looks like a macOS screenshot or screen recording.
+fn is_screen_capture(name: &str) -> bool {
+    let extension = Path::new(name)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase());
+    SCREEN_CAPTURE_PREFIXES
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+        && extension.is_some_and(|ext| SCREEN_CAPTURE_EXTENSIONS.contains(&ext.as_str()))
 }
 
 impl Scanner for ScreenCaptureScanner {
@@ -106,31 +130,67 @@ impl Scanner for ScreenCaptureScanner {
         CategoryType::ScreenCapture
     }
 
-    fn description(&self) -> String {
-        "Screenshots on Desktop.".to_string()
-    }
-
-    fn scan(&self, progress_cb: Option<&(dyn Fn() + Sync)>, allowlist: &Allowlist) -> ScanResult {
-        use crate::constants::DESKTOP_DIR;
-        let path = self.home.join(DESKTOP_DIR);
+    fn scan(&self, progress_cb: Option<&ProgressFn>, options: &ScanOptions) -> ScanResult {
+        let path = self.location.clone();
         let mut items = Vec::new();
 
         if path.exists() {
-            let (_, dt_items) = scan_path(&path, progress_cb, allowlist);
-            // Look for "Screenshot" or "スクリーンショット" prefix
+            let (_, dt_items) = scan_path(&path, progress_cb, options);
             items.extend(dt_items.into_iter().filter(|i| {
-                let name = i.path.file_name().unwrap_or_default().to_string_lossy();
-                name.starts_with("Screenshot") || name.starts_with("スクリーンショット")
+                is_screen_capture(&i.path.file_name().unwrap_or_default().to_string_lossy())
             }));
         }
 
-        ScanResult {
-            category: self.category(),
-            total_size: items.iter().map(|i| i.size).sum(),
-            items,
-            is_selected: false,
-            description: self.description(),
-            root_path: path,
+        ScanResult::new(self.category(), path, items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn screenshot_location_falls_back_to_desktop() -> anyhow::Result<()> {
+        let home = tempfile::tempdir()?;
+        let screenshots = home.path().join("Pictures/Screenshots");
+        fs::create_dir_all(&screenshots)?;
+        let desktop = home.path().join(DESKTOP_DIR);
+
+        assert_eq!(
+            screen_capture_location(home.path(), Some(screenshots.clone())),
+            screenshots
+        );
+        assert_eq!(screen_capture_location(home.path(), None), desktop);
+        assert_eq!(
+            screen_capture_location(home.path(), Some(home.path().join("Gone"))),
+            desktop
+        );
+        assert_eq!(
+            screen_capture_scanner(home.path(), Some(&screenshots), true).location,
+            screenshots
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn matches_screenshots_and_recordings() {
+        for name in [
+            "Screenshot 2024-05-01 at 10.00.00.png",
+            "Screen Shot 2019-01-01 at 9.41.12 AM.png",
+            "Screen Recording 2024-05-01 at 10.00.00.mov",
+            "スクリーンショット 2024-05-01 10.00.00.png",
+            "画面収録 2024-05-01 10.00.00.mov",
+            "Bildschirmfoto 2024-05-01 um 10.00.00.JPG",
+        ] {
+            assert!(is_screen_capture(name), "{name}");
+        }
+        for name in [
+            "Screenshots",
+            "Screenshot notes.txt",
+            "Project Recording.mov",
+            "photo.png",
+        ] {
+            assert!(!is_screen_capture(name), "{name}");
         }
     }
 }
@@ -1,92 +1,415 @@
-use crate::allowlist::Allowlist;
-use crate::model::ScannedItem;
-use jwalk::WalkDir;
+use crate::config::expand_home;
+use crate::constants::NODE_MODULES;
+use crate::model::{ItemKind, ProgressEvent, ScannedItem};
+use crate::scanner::{ProgressFn, ScanOptions};
+use jwalk::{DirEntry, WalkDir};
+use log::{debug, trace, warn};
 use rayon::prelude::*;
+use std::cmp::Reverse;
 use std::fs;
+use std::io;
+use std::os::unix::fs::MetadataExt;
 use std::path::{Path, PathBuf};
-use std::time::SystemTime;
+use std::process::Command;
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant, SystemTime};
 
 /// Helper function to scan a path and return total size and items.
 pub fn scan_path(
     target_path: &Path,
-    progress_cb: Option<&(dyn Fn() + Sync)>,
-    allowlist: &Allowlist,
+    progress_cb: Option<&ProgressFn>,
+    options: &ScanOptions,
 ) -> (u64, Vec<ScannedItem>) {
     if !target_path.exists() {
         return (0, vec![]);
     }
 
+    debug!("Scanning {}", target_path.display());
+    let device = boundary_device(target_path, options);
     let entries: Vec<PathBuf> = match fs::read_dir(target_path) {
-        Ok(read_dir) => read_dir.filter_map(|e| e.ok().map(|e| e.path())).collect(),
-        Err(_) => vec![],
+        Ok(read_dir) => read_dir
+            .filter_map(Result::ok)
+            .filter(|e| e.metadata().is_ok_and(|m| is_on_device(&m, device)))
+            .filter(|e| !skip_offloaded(&e.path(), options))
+            .map(|e| e.path())
+            .collect(),
+        Err(e) => {
+            record_read_error(target_path, &e, options);
+            vec![]
+        }
     };
 
-    let mut items: Vec<ScannedItem> = entries
-        .par_iter()
-        .filter(|path| !allowlist.is_allowed(path))
-        .map(|path| {
-            if let Some(cb) = progress_cb {
-                cb();
+    let entries: Vec<PathBuf> = entries
+        .into_iter()
+        .filter(|path| !options.allowlist.is_allowed(path))
+        .collect();
+
+    let mut items = calculate_all_item_stats(&entries, progress_cb, options);
+
+    let total_size: u64 = items.iter().map(|i| i.size).sum();
+    items.sort_by_key(|i| Reverse(i.size));
+    (total_size, items)
+}
+
+/// Like [`scan_path`], but lists every file below `target_path` as its own item
+/// rather than each top-level entry, so loose files in subfolders can be picked
+/// one by one. Symlinks count as files and are never followed. App bundles and
+/// the like (`.app`, `.framework`, ...) are listed whole, as deleting part of
+/// one would break it.
+pub fn scan_files(
+    target_path: &Path,
+    progress_cb: Option<&ProgressFn>,
+    options: &ScanOptions,
+) -> (u64, Vec<ScannedItem>) {
+    if !target_path.exists() {
+        return (0, vec![]);
+    }
+
+    debug!("Listing files in {}", target_path.display());
+    let device = boundary_device(target_path, options);
+    let allowlist = options.allowlist.clone();
+    let deadline = options.deadline;
+    let cancelled = options.cancelled.clone();
+    let walker = WalkDir::new(target_path)
+        .skip_hidden(false)
+        .follow_links(false)
+        .parallelism(jwalk::Parallelism::Serial)
+        .process_read_dir(move |_, _, (), children| {
+            retain_on_device(children, device);
+            children.retain(|entry| {
+                entry
+                    .as_ref()
+                    .map_or(true, |e| !allowlist.is_allowed(&e.path()))
+            });
+            for entry in children.iter_mut().flatten() {
+                if is_bundle(entry) {
+                    entry.read_children_path = None;
+                }
             }
-            calculate_item_stats(path)
-        })
+            // Out of time or cancelled: keep what was listed but stop descending
+            if cancelled.load(Ordering::Relaxed)
+                || deadline.is_some_and(|deadline| Instant::now() >= deadline)
+            {
+                for entry in children.iter_mut().flatten() {
+                    entry.read_children_path = None;
+                }
+            }
+        });
+
+    let files: Vec<PathBuf> = walker
+        .into_iter()
+        .filter_map(|entry| walk_entry(entry, options))
+        .filter(|e| !e.file_type().is_dir() || is_bundle(e))
+        .map(|e| e.path())
+        .filter(|path| !skip_offloaded(path, options))
         .collect();
 
+    let mut items = calculate_all_item_stats(&files, progress_cb, options);
     let total_size: u64 = items.iter().map(|i| i.size).sum();
-    items.sort_by(|a, b| b.size.cmp(&a.size));
+    items.sort_by_key(|i| Reverse(i.size));
     (total_size, items)
 }
 
+/// Whether a walked entry is a directory that macOS treats as one file, such as an app.
+fn is_bundle(entry: &DirEntry<((), ())>) -> bool {
+    entry.file_type().is_dir() && ItemKind::classify(&entry.path(), true) == ItemKind::Bundle
+}
+
 /// Recursively searches for directories with `target_name` (e.g., "`node_modules`")
+/// up to `max_depth` levels below `root_path`. Found targets are not descended into,
+/// so nested matches (`node_modules/foo/node_modules`) are counted only once.
+/// Hidden directories are skipped unless they are the target (e.g. `.next`), and
+/// installed dependencies in `node_modules` are never searched for other targets.
+/// Allowlisted directories are not descended into.
 pub fn scan_recursive_for_target(
     root_path: &Path,
     target_name: &str,
-    progress_cb: Option<&(dyn Fn() + Sync)>,
-    allowlist: &Allowlist,
+    max_depth: usize,
+    progress_cb: Option<&ProgressFn>,
+    options: &ScanOptions,
 ) -> Vec<ScannedItem> {
-    let walker = WalkDir::new(root_path).skip_hidden(true).max_depth(5);
+    debug!("Searching {} for {target_name}", root_path.display());
+    let device = boundary_device(root_path, options);
+    let allowlist = options.allowlist.clone();
+    let deadline = options.deadline;
+    let cancelled = options.cancelled.clone();
+    let target = target_name.to_string();
+    let walker = WalkDir::new(root_path)
+        .skip_hidden(false)
+        .follow_links(false)
+        .max_depth(max_depth)
+        .parallelism(walk_parallelism(
+            options
+                .jobs
+                .filter(|&jobs| jobs > 0)
+                .unwrap_or_else(rayon::current_num_threads),
+        ))
+        .process_read_dir(move |depth, _, (), children| {
+            retain_on_device(children, device);
+            // Out of time or cancelled: keep what was listed but stop descending
+            if cancelled.load(Ordering::Relaxed)
+                || deadline.is_some_and(|deadline| Instant::now() >= deadline)
+            {
+                for entry in children.iter_mut().flatten() {
+                    entry.read_children_path = None;
+                }
+            }
+            // The root itself arrives without a depth and is never filtered
+            if depth.is_none() {
+                return;
+            }
+            children.retain(|entry| {
+                entry.as_ref().map_or(true, |e| {
+                    let name = e.file_name().to_string_lossy();
+                    (!name.starts_with('.') || name == target)
+                        && !allowlist.excludes_tree(&e.path())
+                })
+            });
+            for entry in children.iter_mut().flatten() {
+                let name = entry.file_name().to_string_lossy();
+                if entry.file_type().is_dir() && (name == target || name == NODE_MODULES) {
+                    entry.read_children_path = None;
+                }
+            }
+        });
 
     let found_paths: Vec<PathBuf> = walker
         .into_iter()
-        .flatten()
+        .filter_map(|entry| walk_entry(entry, options))
         .filter(|e| e.file_type().is_dir() && e.file_name().to_string_lossy() == target_name)
         .map(|e| e.path())
-        .filter(|p| !allowlist.is_allowed(p))
+        .filter(|p| !options.allowlist.is_allowed(p))
         .collect();
 
-    let mut items: Vec<ScannedItem> = found_paths
+    let mut items = calculate_all_item_stats(&found_paths, progress_cb, options);
+    items.sort_by_key(|i| Reverse(i.size));
+    items
+}
+
+/// How directory walks use `threads` threads. They share the global pool, which
+/// `--jobs` caps, unless it has a single thread: scanners already run on that one,
+/// so a walk waiting for it would give up and find nothing.
+fn walk_parallelism(threads: usize) -> jwalk::Parallelism {
+    if threads <= 1 {
+        jwalk::Parallelism::Serial
+    } else {
+        jwalk::Parallelism::RayonDefaultPool {
+            busy_timeout: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Calculates stats for `paths` in parallel, reporting them as found and
+/// then each one as it completes.
+fn calculate_all_item_stats(
+    paths: &[PathBuf],
+    progress_cb: Option<&ProgressFn>,
+    options: &ScanOptions,
+) -> Vec<ScannedItem> {
+    if let Some(cb) = progress_cb {
+        cb(ProgressEvent::Found(paths.len() as u64));
+    }
+
+    paths
         .par_iter()
         .map(|path| {
+            let item = calculate_item_stats(path, options);
             if let Some(cb) = progress_cb {
-                cb();
+                cb(ProgressEvent::Done(item.size));
             }
-            calculate_item_stats(path)
+            item
         })
-        .collect();
+        .collect()
+}
 
-    items.sort_by(|a, b| b.size.cmp(&a.size));
-    items
+/// Records that `path` couldn't be read, for the category's warnings.
+fn record_read_error(path: &Path, err: &io::Error, options: &ScanOptions) {
+    warn!("Failed to read {}: {err}", path.display());
+    if let Ok(mut errors) = options.read_errors.lock() {
+        errors.push((path.to_path_buf(), err.kind()));
+    }
+}
+
+/// Unwraps a walked entry, recording directories the walk couldn't read.
+fn walk_entry<T: jwalk::ClientState>(
+    entry: jwalk::Result<DirEntry<T>>,
+    options: &ScanOptions,
+) -> Option<DirEntry<T>> {
+    let record = |e: &jwalk::Error, path: Option<&Path>| match (path.or(e.path()), e.io_error()) {
+        (Some(path), Some(err)) => record_read_error(path, err, options),
+        _ => debug!("Failed to walk an entry: {e}"),
+    };
+    match entry {
+        Ok(entry) => {
+            // Listing a directory's children fails on the directory's own entry
+            if let Some(e) = &entry.read_children_error {
+                record(e, Some(&entry.path()));
+            }
+            Some(entry)
+        }
+        Err(e) => {
+            record(&e, None);
+            None
+        }
+    }
+}
+
+/// Summarizes read errors per kind, e.g. `3 directories could not be read (permission denied)`.
+pub fn read_warnings(errors: &[(PathBuf, io::ErrorKind)]) -> Vec<String> {
+    let mut counts: Vec<(io::ErrorKind, usize)> = Vec::new();
+    for (_, kind) in errors {
+        match counts.iter_mut().find(|(k, _)| k == kind) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((*kind, 1)),
+        }
+    }
+    counts
+        .into_iter()
+        .map(|(kind, count)| {
+            let noun = if count == 1 {
+                "directory"
+            } else {
+                "directories"
+            };
+            format!("{count} {noun} could not be read ({kind})")
+        })
+        .collect()
+}
+
+/// Reads a path setting with `defaults read <domain> <key>`, if it is set.
+pub fn read_defaults_path(domain: &str, key: &str, home: &Path) -> Option<PathBuf> {
+    let output = Command::new("defaults")
+        .args(["read", domain, key])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_defaults_path(&String::from_utf8_lossy(&output.stdout), home)
+}
+
+/// Parses the output of `defaults read` for a path setting.
+/// Only absolute paths (after expanding `~`) are used.
+fn parse_defaults_path(output: &str, home: &Path) -> Option<PathBuf> {
+    let value = output.trim();
+    let value = value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value);
+    let path = expand_home(Path::new(value), home);
+    path.is_absolute().then_some(path)
+}
+
+/// Set in `st_flags` on files whose contents were evicted to iCloud (`SF_DATALESS`).
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+const SF_DATALESS: u32 = 0x4000_0000;
+
+/// Whether `flags` (a file's `st_flags`) mark it as dataless.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn is_dataless(flags: u32) -> bool {
+    flags & SF_DATALESS != 0
+}
+
+/// Whether the file was offloaded to iCloud ("Optimize Mac Storage"), so its
+/// contents aren't on disk and reading them would download the file.
+#[cfg(target_os = "macos")]
+pub fn is_offloaded(metadata: &fs::Metadata) -> bool {
+    use std::os::macos::fs::MetadataExt as _;
+    is_dataless(metadata.st_flags())
+}
+
+/// Whether the file was offloaded to iCloud. Only macOS offloads files.
+#[cfg(not(target_os = "macos"))]
+pub fn is_offloaded(_metadata: &fs::Metadata) -> bool {
+    false
+}
+
+/// Whether `path` is an offloaded file to leave alone, counting it for the category note.
+fn skip_offloaded(path: &Path, options: &ScanOptions) -> bool {
+    let offloaded = fs::symlink_metadata(path).is_ok_and(|m| is_offloaded(&m));
+    if offloaded {
+        debug!("Skipping {}: offloaded to iCloud", path.display());
+        options.offloaded.fetch_add(1, Ordering::Relaxed);
+    }
+    offloaded
 }
 
-pub fn calculate_item_stats(path: &Path) -> ScannedItem {
+/// Returns the device a walk from `root` must stay on, if `one_file_system` is set.
+fn boundary_device(root: &Path, options: &ScanOptions) -> Option<u64> {
+    if !options.one_file_system {
+        return None;
+    }
+    fs::metadata(root).ok().map(|m| m.dev())
+}
+
+/// Checks whether an entry lives on `device`. Without a device boundary everything matches.
+fn is_on_device(metadata: &fs::Metadata, device: Option<u64>) -> bool {
+    device.is_none_or(|dev| metadata.dev() == dev)
+}
+
+/// Drops directory entries that live on a different filesystem than `device`,
+/// so the walk never descends into them.
+fn retain_on_device(children: &mut Vec<jwalk::Result<DirEntry<((), ())>>>, device: Option<u64>) {
+    if device.is_some() {
+        children.retain(|entry| {
+            entry.as_ref().map_or(true, |e| {
+                e.metadata().is_ok_and(|m| is_on_device(&m, device))
+            })
+        });
+    }
+}
+
+/// Sizes `path` and finds when it last changed. For a directory `modified` is the
+/// newest mtime of the directory itself or anything inside it, so a folder only
+/// counts as old when none of its contents were touched recently either.
+pub fn calculate_item_stats(path: &Path, options: &ScanOptions) -> ScannedItem {
     let mut size = 0;
-    let mut modified = SystemTime::UNIX_EPOCH;
+    let metadata = fs::symlink_metadata(path);
+    // Renaming or removing an entry bumps the directory's own mtime, even when
+    // every remaining child is older. The walk below can't be relied on for it:
+    // it skips entries it fails to read, the root included.
+    let mut modified = metadata
+        .as_ref()
+        .ok()
+        .and_then(|m| m.modified().ok())
+        .unwrap_or(SystemTime::UNIX_EPOCH);
 
-    if let Ok(metadata) = fs::metadata(path)
-        && let Ok(m) = metadata.modified()
-    {
-        modified = m;
+    if let Ok(metadata) = metadata {
+        // A symlink only accounts for the link itself. Walking it would count its
+        // target's contents, which may be a parent directory or even `/`.
+        if metadata.file_type().is_symlink() {
+            return ScannedItem {
+                path: path.to_path_buf(),
+                size: metadata.len(),
+                modified,
+            };
+        }
     }
 
-    // Use serial execution for individual item size calculation to avoid resource exhaustion
+    let device = boundary_device(path, options);
+
+    // Serial on purpose: items are already sized in parallel on the pool `--jobs`
+    // caps, and a walk queued behind them there would time out and size nothing
     for entry in WalkDir::new(path)
         .skip_hidden(false)
+        .follow_links(false)
         .parallelism(jwalk::Parallelism::Serial)
+        .process_read_dir(move |_, _, (), children| retain_on_device(children, device))
         .into_iter()
-        .flatten()
+        .filter_map(|entry| walk_entry(entry, options))
     {
+        if options.should_stop() {
+            debug!("Stopped early while sizing {}", path.display());
+            break;
+        }
+        // Without following links this is the link's own metadata
         if let Ok(metadata) = entry.metadata() {
-            if metadata.is_file() {
+            // Its length is the size in iCloud, not what deleting it would free
+            if is_offloaded(&metadata) {
+                options.offloaded.fetch_add(1, Ordering::Relaxed);
+                continue;
+            }
+            if metadata.is_file() || metadata.file_type().is_symlink() {
                 size += metadata.len();
             }
             if let Ok(m) = metadata.modified()
@@ -97,6 +420,7 @@ pub fn calculate_item_stats(path: &Path) -> ScannedItem {
         }
     }
 
+    trace!("Visited {} ({size} bytes)", path.display());
     ScannedItem {
         path: path.to_path_buf(),
         size,
@@ -111,10 +435,121 @@ mod tests {
     use anyhow::Result;
     use std::fs::File;
     use std::io::Write;
+    use std::sync::{Arc, Mutex};
     use std::thread;
     use std::time::Duration;
     use tempfile::tempdir;
 
+    #[test]
+    fn dataless_flag_marks_offloaded_files() -> Result<()> {
+        assert!(is_dataless(SF_DATALESS));
+        assert!(is_dataless(SF_DATALESS | 0x8000)); // with UF_HIDDEN
+        assert!(!is_dataless(0));
+        assert!(!is_dataless(0x8000));
+
+        // Files created here are always local
+        let dir = tempdir()?;
+        let file = dir.path().join("local.txt");
+        File::create(&file)?;
+        assert!(!is_offloaded(&fs::metadata(&file)?));
+        let options = ScanOptions::default();
+        assert!(!skip_offloaded(&file, &options));
+        assert_eq!(options.offloaded.load(Ordering::Relaxed), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn directory_modified_is_its_newest_content() -> Result<()> {
+        let dir = tempdir()?;
+        let folder = dir.path().join("folder");
+        fs::create_dir(&folder)?;
+        let child = folder.join("file.txt");
+        File::create(&child)?;
+        let old = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let new = old + Duration::from_secs(1_000);
+        let set_mtime = |path: &Path, time| File::open(path)?.set_modified(time);
+        let options = ScanOptions::default();
+
+        // The directory itself changed after its only child
+        set_mtime(&child, old)?;
+        set_mtime(&folder, new)?;
+        assert_eq!(calculate_item_stats(&folder, &options).modified, new);
+
+        // A child changed after the directory
+        set_mtime(&child, new)?;
+        set_mtime(&folder, old)?;
+        assert_eq!(calculate_item_stats(&folder, &options).modified, new);
+
+        // An empty directory is as old as itself
+        fs::remove_file(&child)?;
+        set_mtime(&folder, old)?;
+        assert_eq!(calculate_item_stats(&folder, &options).modified, old);
+        Ok(())
+    }
+
+    #[test]
+    fn read_errors_are_summarized_per_kind() {
+        let errors = vec![
+            (PathBuf::from("/a"), io::ErrorKind::PermissionDenied),
+            (PathBuf::from("/b"), io::ErrorKind::NotFound),
+            (PathBuf::from("/c"), io::ErrorKind::PermissionDenied),
+        ];
+        assert_eq!(
+            read_warnings(&errors),
+            vec![
+                "2 directories could not be read (permission denied)",
+                "1 directory could not be read (entity not found)",
+            ]
+        );
+        assert!(read_warnings(&[]).is_empty());
+    }
+
+    #[test]
+    fn unreadable_child_records_a_warning() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+        // Permissions don't stop root
+        if crate::cleaner::is_root() {
+            return Ok(());
+        }
+        let dir = tempdir()?;
+        let locked = dir.path().join("locked");
+        fs::create_dir(&locked)?;
+        File::create(locked.join("secret"))?;
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o000))?;
+
+        let options = ScanOptions::default();
+        let (_, items) = scan_path(dir.path(), None, &options);
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755))?;
+
+        assert_eq!(items.len(), 1);
+        let errors = options.read_errors.lock().unwrap();
+        assert_eq!(
+            read_warnings(&errors),
+            vec!["1 directory could not be read (permission denied)"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_defaults_paths() {
+        let home = Path::new("/Users/test");
+        assert_eq!(
+            parse_defaults_path("/Volumes/Fast/DerivedData\n", home),
+            Some(PathBuf::from("/Volumes/Fast/DerivedData"))
+        );
+        assert_eq!(
+            parse_defaults_path("\"~/Build Output/DerivedData\"\n", home),
+            Some(PathBuf::from("/Users/test/Build Output/DerivedData"))
+        );
+        // `defaults write com.apple.screencapture location` keeps whatever was typed
+        assert_eq!(
+            parse_defaults_path("~/Pictures/Screenshots/\n", home),
+            Some(PathBuf::from("/Users/test/Pictures/Screenshots"))
+        );
+        assert_eq!(parse_defaults_path("", home), None);
+        assert_eq!(parse_defaults_path("DerivedData\n", home), None);
+    }
+
     #[test]
     fn scan_path_structure() -> Result<()> {
         let dir = tempdir()?;
@@ -132,19 +567,45 @@ mod tests {
         let mut f2 = File::create(folder_b.join("file2.txt"))?;
         f2.write_all(&[0u8; 200])?;
 
-        let allowlist = Allowlist::new(vec![]);
-        let (total_size, items) = scan_path(root, None, &allowlist);
+        let options = ScanOptions::default();
+        let (total_size, items) = scan_path(root, None, &options);
 
         assert_eq!(total_size, 300);
         assert_eq!(items.len(), 2);
         Ok(())
     }
 
+    #[test]
+    fn scan_path_reports_growing_totals() -> Result<()> {
+        let dir = tempdir()?;
+        for (name, size) in [("a", 100), ("b", 200), ("c", 300)] {
+            File::create(dir.path().join(name))?.write_all(&vec![0u8; size])?;
+        }
+
+        let partials = Arc::new(Mutex::new(vec![0u64]));
+        let recorded = Arc::clone(&partials);
+        let cb = move |event: ProgressEvent| {
+            if let ProgressEvent::Done(bytes) = event {
+                let mut partials = recorded.lock().unwrap();
+                let last = *partials.last().unwrap();
+                partials.push(last + bytes);
+            }
+        };
+        let options = ScanOptions::default();
+        let (total_size, _) = scan_path(dir.path(), Some(&cb), &options);
+
+        let partials = partials.lock().unwrap();
+        assert!(partials.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(partials.last(), Some(&total_size));
+        assert_eq!(total_size, 600);
+        Ok(())
+    }
+
     #[test]
     fn scan_path_empty_dir() -> Result<()> {
         let dir = tempdir()?;
-        let allowlist = Allowlist::new(vec![]);
-        let (total_size, items) = scan_path(dir.path(), None, &allowlist);
+        let options = ScanOptions::default();
+        let (total_size, items) = scan_path(dir.path(), None, &options);
         assert_eq!(total_size, 0);
         assert!(items.is_empty());
         Ok(())
@@ -153,8 +614,8 @@ mod tests {
     #[test]
     fn scan_non_existent_path() {
         let path = PathBuf::from("/path/to/non/existent/directory/sukkiri_test_random_12345");
-        let allowlist = Allowlist::new(vec![]);
-        let (total_size, items) = scan_path(&path, None, &allowlist);
+        let options = ScanOptions::default();
+        let (total_size, items) = scan_path(&path, None, &options);
         assert_eq!(total_size, 0);
         assert!(items.is_empty());
     }
@@ -178,8 +639,8 @@ mod tests {
         let mut f2 = File::create(nm2.join("index.js"))?;
         f2.write_all(&[0u8; 200])?;
 
-        let allowlist = Allowlist::new(vec![]);
-        let found_items = scan_recursive_for_target(root, "node_modules", None, &allowlist);
+        let options = ScanOptions::default();
+        let found_items = scan_recursive_for_target(root, "node_modules", 5, None, &options);
 
         assert_eq!(found_items.len(), 2);
         assert_eq!(found_items[0].size, 200);
@@ -187,4 +648,229 @@ mod tests {
 
         Ok(())
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlink_cycle_is_not_followed() -> Result<()> {
+        use std::os::unix::fs::symlink;
+
+        let dir = tempdir()?;
+        let root = dir.path();
+
+        let item = root.join("Item");
+        fs::create_dir(&item)?;
+        let mut f = File::create(item.join("data.bin"))?;
+        f.write_all(&[0u8; 100])?;
+        // A link back to its own parent and one to the filesystem root
+        symlink(&item, item.join("loop"))?;
+        symlink("/", item.join("root"))?;
+        // A top-level entry that is itself a link to the scanned directory
+        symlink(root, root.join("up"))?;
+
+        let options = ScanOptions::default();
+        let stats = calculate_item_stats(&item, &options);
+        assert!(
+            stats.size >= 100 && stats.size < 4096,
+            "size: {}",
+            stats.size
+        );
+
+        let link_stats = calculate_item_stats(&root.join("up"), &options);
+        assert!(link_stats.size < 4096, "size: {}", link_stats.size);
+
+        let (total_size, items) = scan_path(root, None, &options);
+        assert_eq!(items.len(), 2);
+        assert!(total_size < 8192, "total: {total_size}");
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn recursive_search_does_not_follow_symlinks() -> Result<()> {
+        use std::os::unix::fs::symlink;
+
+        let dir = tempdir()?;
+        let project = dir.path().join("Project");
+        fs::create_dir_all(project.join("node_modules"))?;
+        symlink(&project, project.join("self"))?;
+
+        let options = ScanOptions::default();
+        let found = scan_recursive_for_target(dir.path(), "node_modules", 5, None, &options);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].path, project.join("node_modules"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn device_boundary_predicate() -> Result<()> {
+        let dir = tempdir()?;
+        let metadata = fs::metadata(dir.path())?;
+
+        assert!(is_on_device(&metadata, None));
+        assert!(is_on_device(&metadata, Some(metadata.dev())));
+        assert!(!is_on_device(
+            &metadata,
+            Some(metadata.dev().wrapping_add(1))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn boundary_device_only_with_one_file_system() -> Result<()> {
+        let dir = tempdir()?;
+        assert_eq!(boundary_device(dir.path(), &ScanOptions::default()), None);
+
+        let options = ScanOptions {
+            one_file_system: true,
+            ..ScanOptions::default()
+        };
+        assert_eq!(
+            boundary_device(dir.path(), &options),
+            Some(fs::metadata(dir.path())?.dev())
+        );
+
+        // Everything in a single temp dir lives on the same filesystem
+        File::create(dir.path().join("file.txt"))?.write_all(&[0u8; 10])?;
+        let (total_size, _) = scan_path(dir.path(), None, &options);
+        assert_eq!(total_size, 10);
+        Ok(())
+    }
+
+    #[test]
+    fn recursive_search_skips_nested_targets() -> Result<()> {
+        let dir = tempdir()?;
+        let outer = dir.path().join("App/node_modules");
+        let nested = outer.join("dep/node_modules");
+        fs::create_dir_all(&nested)?;
+        File::create(nested.join("index.js"))?.write_all(&[0u8; 50])?;
+
+        let options = ScanOptions::default();
+        let found = scan_recursive_for_target(dir.path(), "node_modules", 5, None, &options);
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].path, outer);
+        // The nested copy is still part of the outer directory's size
+        assert_eq!(found[0].size, 50);
+        Ok(())
+    }
+
+    #[test]
+    fn recursive_search_skips_allowlisted_trees() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir()?;
+        let kept = dir.path().join("Kept");
+        fs::create_dir_all(kept.join("app/node_modules"))?;
+        fs::create_dir_all(kept.join("tool/node_modules"))?;
+        let locked = kept.join("locked");
+        fs::create_dir(&locked)?;
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o000))?;
+
+        let options = ScanOptions {
+            allowlist: Allowlist::new(vec![kept.display().to_string()]),
+            ..ScanOptions::default()
+        };
+        let found = scan_recursive_for_target(dir.path(), "node_modules", 5, None, &options);
+        assert!(found.is_empty());
+        // The locked folder would fail to read had the walk gone inside
+        assert!(options.read_errors.lock().unwrap().is_empty());
+
+        // A re-included folder inside is still searched
+        let options = ScanOptions {
+            allowlist: Allowlist::new(vec![
+                kept.display().to_string(),
+                format!("!{}", kept.join("tool").display()),
+            ]),
+            ..ScanOptions::default()
+        };
+        let found = scan_recursive_for_target(dir.path(), "node_modules", 5, None, &options);
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755))?;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].path, kept.join("tool/node_modules"));
+        Ok(())
+    }
+
+    #[test]
+    fn recursive_search_respects_max_depth() -> Result<()> {
+        let dir = tempdir()?;
+        fs::create_dir_all(dir.path().join("a/b/c/node_modules"))?;
+
+        let options = ScanOptions::default();
+        assert!(
+            scan_recursive_for_target(dir.path(), "node_modules", 3, None, &options).is_empty()
+        );
+        assert_eq!(
+            scan_recursive_for_target(dir.path(), "node_modules", 4, None, &options).len(),
+            1
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn cancelled_sizing_returns_partial_size() -> Result<()> {
+        let dir = tempdir()?;
+        for i in 0..20 {
+            let sub = dir.path().join(format!("dir{i}"));
+            fs::create_dir(&sub)?;
+            for j in 0..50 {
+                File::create(sub.join(format!("file{j}")))?.write_all(&[0u8; 10])?;
+            }
+        }
+
+        let total = 20 * 50 * 10;
+        let options = ScanOptions::default();
+        assert_eq!(calculate_item_stats(dir.path(), &options).size, total);
+
+        // Cancel after ever longer delays until it happens in the middle of the
+        // walk, so the check inside the loop is what stops it
+        let mut delay = Duration::from_micros(10);
+        let partial = loop {
+            let options = ScanOptions::default();
+            let cancelled = Arc::clone(&options.cancelled);
+            let cancel_later = thread::spawn(move || {
+                thread::sleep(delay);
+                cancelled.store(true, Ordering::Relaxed);
+            });
+            let size = calculate_item_stats(dir.path(), &options).size;
+            cancel_later.join().unwrap();
+            if (size > 0 && size < total) || delay > Duration::from_secs(1) {
+                break size;
+            }
+            delay = delay * 3 / 2;
+        };
+        assert!(partial > 0 && partial < total, "sized {partial}");
+        Ok(())
+    }
+
+    #[test]
+    fn items_are_sized_with_any_number_of_jobs() -> Result<()> {
+        let dir = tempdir()?;
+        for folder in ["a", "b", "c", "d"] {
+            fs::create_dir_all(dir.path().join(folder).join("nested"))?;
+            File::create(dir.path().join(folder).join("nested/blob"))?.write_all(&[0u8; 100])?;
+            File::create(dir.path().join(folder).join("top"))?.write_all(&[0u8; 10])?;
+        }
+        for jobs in [1, 4] {
+            let options = ScanOptions {
+                jobs: Some(jobs),
+                ..ScanOptions::default()
+            };
+            let pool = crate::scanner::thread_pool_builder(options.jobs).build()?;
+            let (total, items) = pool.install(|| scan_path(dir.path(), None, &options));
+            assert_eq!(total, 440, "{jobs} jobs");
+            assert!(items.iter().all(|item| item.size == 110), "{jobs} jobs");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn single_job_walks_serially() {
+        assert!(matches!(walk_parallelism(1), jwalk::Parallelism::Serial));
+        assert!(matches!(
+            walk_parallelism(8),
+            jwalk::Parallelism::RayonDefaultPool { .. }
+        ));
+    }
 }
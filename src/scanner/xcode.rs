@@ -1,16 +1,30 @@
-use crate::constants::{CORE_SIMULATOR, XCODE_ARCHIVES, XCODE_DERIVED_DATA, XCODE_DEVICE_SUPPORT};
-use crate::model::CategoryType;
+use crate::constants::{
+    CORE_SIMULATOR_CACHES, CORE_SIMULATOR_IMAGES, XCODE_ARCHIVES, XCODE_DERIVED_DATA,
+    XCODE_DEVICE_SUPPORT,
+};
+use crate::model::{CategoryType, ScannedItem};
 use crate::scanner::PathScanner;
-use std::path::Path;
+use crate::scanner::utils::read_defaults_path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
-pub fn xcode_scanner(home: &Path) -> PathScanner {
+/// Without `run_defaults` only the default `DerivedData` location is scanned.
+pub fn xcode_scanner(home: &Path, run_defaults: bool) -> PathScanner {
     let mut paths = Vec::new();
 
-    // DerivedData
+    // DerivedData, plus wherever Xcode was told to put it instead
     let derived_path = home.join(XCODE_DERIVED_DATA);
     if derived_path.exists() {
         paths.push(derived_path);
     }
+    if let Some(custom) = run_defaults
+        .then(|| custom_derived_data_location(home))
+        .flatten()
+        && custom.exists()
+        && !paths.contains(&custom)
+    {
+        paths.push(custom);
+    }
 
     // Archives
     let archives_path = home.join(XCODE_ARCHIVES);
@@ -24,15 +38,127 @@ pub fn xcode_scanner(home: &Path) -> PathScanner {
         paths.push(device_support_path);
     }
 
-    // CoreSimulator
-    let core_sim_path = home.join(CORE_SIMULATOR);
-    if core_sim_path.exists() {
-        paths.push(core_sim_path);
+    // CoreSimulator caches and images, but not the simulator devices
+    for dir in [CORE_SIMULATOR_CACHES, CORE_SIMULATOR_IMAGES] {
+        let core_sim_path = home.join(dir);
+        if core_sim_path.exists() {
+            paths.push(core_sim_path);
+        }
     }
 
     PathScanner {
         category: CategoryType::XcodeJunk,
-        description: "Xcode build artifacts, archives, and device support.".to_string(),
         paths,
+        flatten_files: false,
+    }
+}
+
+/// Reads the `DerivedData` location set in Xcode's preferences, if any.
+fn custom_derived_data_location(home: &Path) -> Option<PathBuf> {
+    read_defaults_path("com.apple.dt.Xcode", "IDECustomDerivedDataLocation", home)
+}
+
+/// Returns the most recently modified `DerivedData` folder of each project,
+/// which Xcode is most likely still building from.
+/// Folders are named `<Project>-<hash>`, so the project is the part before the last `-`.
+/// `DerivedData` may have been moved, so anything outside the other Xcode folders counts.
+pub fn latest_derived_data<'a>(
+    items: impl IntoIterator<Item = &'a ScannedItem>,
+) -> HashSet<PathBuf> {
+    let mut latest: HashMap<String, &ScannedItem> = HashMap::new();
+    for item in items {
+        if item.path.parent().is_none_or(|parent| {
+            [
+                XCODE_ARCHIVES,
+                XCODE_DEVICE_SUPPORT,
+                CORE_SIMULATOR_CACHES,
+                CORE_SIMULATOR_IMAGES,
+            ]
+            .iter()
+            .any(|dir| parent.ends_with(dir))
+        }) {
+            continue;
+        }
+        let name = item.path.file_name().unwrap_or_default().to_string_lossy();
+        let project = name.rsplit_once('-').map_or(&*name, |(project, _)| project);
+        latest
+            .entry(project.to_string())
+            .and_modify(|newest| {
+                if item.modified > newest.modified {
+                    *newest = item;
+                }
+            })
+            .or_insert(item);
+    }
+    latest.into_values().map(|item| item.path.clone()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::{ScanOptions, Scanner};
+    use std::fs;
+    use std::time::{Duration, SystemTime};
+
+    fn item(path: &Path, age_days: u64) -> ScannedItem {
+        ScannedItem {
+            path: path.to_path_buf(),
+            size: 1,
+            modified: SystemTime::now() - Duration::from_secs(age_days * 86_400),
+        }
+    }
+
+    #[test]
+    fn keeps_newest_derived_data_per_project() {
+        let derived = Path::new("/Users/test").join(XCODE_DERIVED_DATA);
+        let custom = Path::new("/Volumes/Fast/DerivedData");
+        let items = vec![
+            item(&derived.join("MyApp-abcdefgh"), 30),
+            item(&derived.join("MyApp-ijklmnop"), 1),
+            item(&derived.join("Other-qrstuvwx"), 90),
+            item(&custom.join("Fast-abcdefgh"), 5),
+            item(
+                &Path::new("/Users/test")
+                    .join(XCODE_ARCHIVES)
+                    .join("2024-01-01"),
+                0,
+            ),
+        ];
+
+        assert_eq!(
+            latest_derived_data(&items),
+            HashSet::from([
+                derived.join("MyApp-ijklmnop"),
+                derived.join("Other-qrstuvwx"),
+                custom.join("Fast-abcdefgh")
+            ])
+        );
+    }
+
+    #[test]
+    fn core_simulator_caches_are_scanned_without_devices() -> anyhow::Result<()> {
+        let home = tempfile::tempdir()?;
+        let core_sim = home.path().join("Library/Developer/CoreSimulator");
+        for dir in ["Caches/dyld", "Images/iOS-17", "Devices/1234-ABCD/data"] {
+            fs::create_dir_all(core_sim.join(dir))?;
+            fs::write(core_sim.join(dir).join("blob"), b"x")?;
+        }
+
+        let scanner = xcode_scanner(home.path(), false);
+        assert_eq!(
+            scanner.paths,
+            vec![
+                home.path().join(CORE_SIMULATOR_CACHES),
+                home.path().join(CORE_SIMULATOR_IMAGES)
+            ]
+        );
+        let result = scanner.scan(None, &ScanOptions::default());
+        let mut paths: Vec<PathBuf> = result.items.iter().map(|i| i.path.clone()).collect();
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![core_sim.join("Caches/dyld"), core_sim.join("Images/iOS-17")]
+        );
+        Ok(())
     }
 }
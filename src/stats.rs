@@ -0,0 +1,77 @@
+use crate::config::config_dir;
+use anyhow::Result;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File in the configuration directory keeping the lifetime totals.
+const STATS_FILE: &str = "stats.json";
+
+/// Totals kept across sessions.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+    /// Bytes freed by every clean so far.
+    #[serde(default)]
+    pub reclaimed: u64,
+}
+
+impl Stats {
+    /// Reads the stats at `path`, starting from zero if the file is missing or corrupt.
+    fn load(path: &Path) -> Self {
+        let Ok(content) = fs::read_to_string(path) else {
+            return Self::default();
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            warn!("Ignoring corrupt {}: {e}", path.display());
+            Self::default()
+        })
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Adds `freed` bytes to the stats at `path` and returns the new totals.
+    fn record(path: &Path, freed: u64) -> Self {
+        let mut stats = Self::load(path);
+        stats.reclaimed = stats.reclaimed.saturating_add(freed);
+        if let Err(e) = stats.save(path) {
+            warn!("Failed to write {}: {e}", path.display());
+        }
+        stats
+    }
+}
+
+fn stats_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join(STATS_FILE))
+}
+
+/// Adds the bytes a clean freed to the lifetime total and returns the new totals.
+pub fn record_freed(freed: u64) -> Stats {
+    stats_path().map_or_else(Stats::default, |path| Stats::record(&path, freed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reclaimed_accumulates_across_sessions() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("sukkiri").join(STATS_FILE);
+        assert_eq!(Stats::load(&path), Stats::default());
+
+        assert_eq!(Stats::record(&path, 1024).reclaimed, 1024);
+        assert_eq!(Stats::record(&path, 512).reclaimed, 1536);
+        assert_eq!(Stats::load(&path).reclaimed, 1536);
+
+        fs::write(&path, "{ not json")?;
+        assert_eq!(Stats::record(&path, 10).reclaimed, 10);
+        Ok(())
+    }
+}
@@ -1,15 +1,36 @@
 use crate::allowlist::Allowlist;
-use crate::cleaner;
-use crate::model::ScanResult;
-use crate::model::{CategoryType, ScanProgress};
-use crate::scanner;
+use crate::cleaner::{self, CleanPlan, CleanReport, DeleteMethod};
+use crate::constants::{
+    BIGGEST_WINS, DISK_CRITICAL_FREE_RATIO, DISK_REFRESH_SECS, DOWNLOADS_MIN_AGE_DAYS,
+    DUPLICATE_MIN_SIZE, PROGRESS_INTERVAL_MS, SECS_PER_DAY,
+};
+use crate::interrupt;
+use crate::model::{CategoryType, ItemSort, ProgressEvent, Risk, ScanProgress};
+use crate::model::{ScanResult, ScannedItem};
+use crate::pins;
+use crate::scanner::container::ContainerEngine;
+use crate::scanner::{self, ScanOptions, duplicates, xcode};
+use crate::stats;
+use crate::ui::theme::Theme;
+use crate::undo;
+use crate::units::{self, Units};
 use anyhow::Result;
-use humansize::{BINARY, format_size};
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use log::info;
+use ratatui::layout::{Position, Rect};
 use ratatui::widgets::ListState;
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
+use std::fmt::Write as _;
+use std::io::{self, Write as _};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::{Duration, Instant, SystemTime};
 use sysinfo::Disks;
 
 pub enum AppState {
@@ -18,29 +39,254 @@ pub enum AppState {
     Cleaning,
     Scanning,     // New state for scanning
     Done(String), // Done message
+    ConfirmQuit,  // Asking before discarding pending selections
 }
 
+/// Which list the Browsing screen shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum View {
+    Categories,
+    /// The largest items across every category.
+    TopItems,
+}
+
+/// Which pane of the categories view the navigation keys move in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Focus {
+    Categories,
+    /// The items of the highlighted category.
+    Details,
+}
+
+/// How urgently the startup disk needs space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiskPressure {
+    Normal,
+    /// Less than `DISK_CRITICAL_FREE_RATIO` of the disk is free.
+    Critical,
+}
+
+impl DiskPressure {
+    pub fn from_free_ratio(free_ratio: f64) -> Self {
+        if free_ratio < DISK_CRITICAL_FREE_RATIO {
+            Self::Critical
+        } else {
+            Self::Normal
+        }
+    }
+}
+
+/// A list of items to clean, grouped by category.
+pub type Batches = Vec<(CategoryType, Vec<ScannedItem>)>;
+
+/// Sent by the cleaning thread: a summary and the paths left behind, or an error.
+pub type CleanOutcome = Result<(String, CleanReport), String>;
+
+#[allow(clippy::struct_excessive_bools)]
 pub struct App {
     pub results: Vec<ScanResult>,
     pub list_state: ListState,
     pub state: AppState,
     pub disks: Disks,
     // Channel receiver for cleaning thread results
-    pub cleaning_rx: Option<mpsc::Receiver<Result<String, String>>>,
+    pub cleaning_rx: Option<mpsc::Receiver<CleanOutcome>>,
     // Scanning
     pub scan_rx: Option<mpsc::Receiver<ScanUpdate>>,
     pub scan_progress: HashMap<CategoryType, ScanProgress>,
     pub total_categories: usize,
+    pub scan_options: Arc<ScanOptions>,
+    // Bytes to auto-select once scanning completes (`--free`)
+    pub auto_select_target: Option<u64>,
+    // Last rendered pane areas, used to map mouse events
+    pub list_area: Rect,
+    pub details_area: Rect,
+    pub details_scroll: usize,
+    pub focus: Focus,
+    /// How the details pane orders the items of every category.
+    pub item_sort: ItemSort,
+    pub theme: Theme,
+    pub delete_mode: DeleteMethod,
+    // Move cleaned items here instead of trashing or deleting them (`--move-to`)
+    pub move_to: Option<PathBuf>,
+    pub view: View,
+    pub top_state: ListState,
+    // Items staged for cleaning instead of the selected categories
+    pub plan: Option<Batches>,
+    // Set by Ctrl-C; a running clean stops after the current item
+    pub interrupted: Arc<AtomicBool>,
+    // Highlighted category in the confirmation popup
+    pub review_index: usize,
+    // Event loop iterations so far, to animate the scanning spinner
+    pub tick: usize,
+    // Items handed to the cleaning thread, removed from `results` once it succeeds
+    pub cleaning_batches: Batches,
+    // One-off note shown in the footer until the next key press
+    pub message: Option<String>,
+    // Paths the last clean couldn't delete without administrator rights
+    pub denied: Vec<PathBuf>,
+    pub units: Units,
+    // Downloads newer than this stay unselected when the category is toggled on
+    pub downloads_min_age: Duration,
+    // Items left out of their selected category
+    pub excluded: HashSet<PathBuf>,
+    // Only list installers (.dmg, .pkg, .zip, ...) in the details pane
+    pub installers_only: bool,
+    // Look for identical files once scanning completes (`--duplicates`)
+    pub find_duplicates: bool,
+    // Files with an identical copy elsewhere in the results
+    pub duplicates: HashSet<PathBuf>,
+    // The duplicates found by the background hashing, once it is done
+    pub duplicates_rx: Option<mpsc::Receiver<HashSet<PathBuf>>>,
+    // Running as root, so system locations can be cleaned
+    pub is_root: bool,
+    // Read again when a clean is confirmed, to catch rules added since the scan
+    pub allowlist: Allowlist,
+    // Leave each project's newest Xcode DerivedData unselected
+    pub keep_latest_derived_data: bool,
+    // How many of the newest items stay unselected, per category (`keep_recent`)
+    pub keep_recent: HashMap<CategoryType, usize>,
+    // Fresh results of categories re-scanned after cleaning
+    pub rescan_tx: mpsc::Sender<ScanResult>,
+    pub rescan_rx: mpsc::Receiver<ScanResult>,
+    // Re-scans started but not yet swapped in
+    pub pending_rescans: usize,
+    // Re-scan everything this often while browsing (`--watch`)
+    pub watch_interval: Option<Duration>,
+    // When the results were last refreshed (or first scanned)
+    pub last_refresh: Instant,
+    /// When the free space in the header was last read.
+    pub last_disk_refresh: Instant,
+    // Categories kept at the top of the list, above the size-sorted rest
+    pub pinned: HashSet<CategoryType>,
+    // Children of items expanded in the details pane, listed below their parent
+    pub expanded: HashMap<PathBuf, Vec<ScannedItem>>,
+    // The item being expanded in the background
+    pub expanding: Option<Expansion>,
+}
+
+/// An item whose children are being listed in the background.
+pub struct Expansion {
+    pub path: PathBuf,
+    // Stops the listing, e.g. when `e` is pressed again
+    pub cancel: Arc<AtomicBool>,
+    pub rx: mpsc::Receiver<Vec<ScannedItem>>,
+}
+
+/// Arguments to `open` that reveal `path` in Finder: directories are opened,
+/// files are selected in their folder. Virtual Docker paths have no location.
+fn reveal_args(path: &Path, is_dir: bool) -> Option<Vec<OsString>> {
+    if ContainerEngine::parse_path(path).is_some() {
+        return None;
+    }
+    let mut args = Vec::new();
+    if !is_dir {
+        args.push(OsString::from("-R"));
+    }
+    args.push(path.as_os_str().to_owned());
+    Some(args)
 }
 
+/// The paths of the `count` most recently modified items.
+fn newest_items(items: &[ScannedItem], count: usize) -> Vec<PathBuf> {
+    let mut items: Vec<&ScannedItem> = items.iter().collect();
+    items.sort_by_key(|item| Reverse(item.modified));
+    items
+        .into_iter()
+        .take(count)
+        .map(|item| item.path.clone())
+        .collect()
+}
+
+/// What copying `path` puts on the clipboard: a container image's ID, or else the path.
+fn clipboard_text(path: &Path) -> String {
+    ContainerEngine::parse_path(path)
+        .map_or_else(|| path.to_string_lossy().into_owned(), |(_, id)| id)
+}
+
+/// Puts `text` on the system clipboard with `pbcopy`.
+fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    let mut child = Command::new("pbcopy").stdin(Stdio::piped()).spawn()?;
+    // Closing stdin when it is dropped lets pbcopy finish
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    child.wait()?;
+    Ok(())
+}
+
+/// Number of items shown in the top items view.
+pub const TOP_ITEMS_LIMIT: usize = 50;
+
+/// Width of the list highlight symbol ("> ") that precedes every row.
+const HIGHLIGHT_SYMBOL_WIDTH: u16 = 2;
+/// Width of the checkbox ("[x]") at the start of each category row.
+const CHECKBOX_WIDTH: u16 = 3;
+
 pub enum ScanUpdate {
     Progress(ScanProgress),
     Result(ScanResult),
 }
 
+/// Adds up a scanner's progress and passes it on at most once per `interval`,
+/// so a huge tree doesn't queue a message per entry for the UI thread to drain.
+struct ProgressThrottle {
+    category: CategoryType,
+    interval: Duration,
+    // Items processed, items found and bytes not passed on yet, and when the last batch was
+    pending: Mutex<((u64, u64, u64), Instant)>,
+}
+
+impl ProgressThrottle {
+    fn new(category: CategoryType, interval: Duration, now: Instant) -> Self {
+        Self {
+            category,
+            interval,
+            pending: Mutex::new(((0, 0, 0), now)),
+        }
+    }
+
+    /// Adds `event`, returning what has piled up once `interval` has passed since the last batch.
+    fn record(&self, event: ProgressEvent, now: Instant) -> Option<ScanProgress> {
+        let mut pending = self.pending.lock().ok()?;
+        let ((items_count, items_total, bytes), last_sent) = &mut *pending;
+        match event {
+            ProgressEvent::Found(n) => *items_total += n,
+            ProgressEvent::Done(size) => {
+                *items_count += 1;
+                *bytes += size;
+            }
+        }
+        if now.duration_since(*last_sent) < self.interval {
+            return None;
+        }
+        *last_sent = now;
+        let delta = std::mem::take(&mut pending.0);
+        Some(self.progress(delta))
+    }
+
+    /// Whatever hasn't been passed on yet.
+    fn flush(&self) -> Option<ScanProgress> {
+        let delta = std::mem::take(&mut self.pending.lock().ok()?.0);
+        (delta != (0, 0, 0)).then(|| self.progress(delta))
+    }
+
+    fn progress(&self, (items_count, items_total, bytes): (u64, u64, u64)) -> ScanProgress {
+        ScanProgress {
+            category: self.category,
+            items_count,
+            items_total,
+            bytes,
+            status: "Scanning...".to_string(),
+            duration: Duration::ZERO,
+        }
+    }
+}
+
 impl App {
-    pub fn new_scanning() -> Self {
+    pub fn new_scanning(scan_options: ScanOptions) -> Self {
         let disks = Disks::new_with_refreshed_list();
+        let (rescan_tx, rescan_rx) = mpsc::channel();
+        let allowlist = scan_options.allowlist.clone();
         Self {
             results: Vec::new(),
             list_state: ListState::default(),
@@ -50,9 +296,89 @@ impl App {
             scan_rx: None,
             scan_progress: HashMap::new(),
             total_categories: 0,
+            scan_options: Arc::new(scan_options),
+            auto_select_target: None,
+            list_area: Rect::default(),
+            details_area: Rect::default(),
+            details_scroll: 0,
+            focus: Focus::Categories,
+            item_sort: ItemSort::default(),
+            theme: Theme::default(),
+            delete_mode: DeleteMethod::default(),
+            move_to: None,
+            view: View::Categories,
+            top_state: ListState::default(),
+            plan: None,
+            interrupted: interrupt::flag(),
+            tick: 0,
+            review_index: 0,
+            cleaning_batches: Vec::new(),
+            message: None,
+            denied: Vec::new(),
+            units: Units::default(),
+            downloads_min_age: Duration::from_secs(DOWNLOADS_MIN_AGE_DAYS * SECS_PER_DAY),
+            excluded: HashSet::new(),
+            installers_only: false,
+            find_duplicates: false,
+            duplicates: HashSet::new(),
+            duplicates_rx: None,
+            is_root: cleaner::is_root(),
+            allowlist,
+            keep_latest_derived_data: true,
+            keep_recent: HashMap::new(),
+            rescan_tx,
+            rescan_rx,
+            pending_rescans: 0,
+            watch_interval: None,
+            last_refresh: Instant::now(),
+            last_disk_refresh: Instant::now(),
+            pinned: HashSet::new(),
+            expanded: HashMap::new(),
+            expanding: None,
         }
     }
 
+    /// Total and available bytes of the startup disk.
+    pub fn root_disk_space(&self) -> Option<(u64, u64)> {
+        self.disks
+            .list()
+            .iter()
+            .find(|d| d.mount_point() == Path::new("/"))
+            .map(|disk| (disk.total_space(), disk.available_space()))
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    pub fn disk_free_ratio(&self) -> Option<f64> {
+        self.root_disk_space()
+            .filter(|(total, _)| *total > 0)
+            .map(|(total, available)| available as f64 / total as f64)
+    }
+
+    pub fn disk_pressure(&self) -> DiskPressure {
+        self.disk_free_ratio()
+            .map_or(DiskPressure::Normal, DiskPressure::from_free_ratio)
+    }
+
+    /// The largest categories, highlighted as the biggest wins when the disk is critically full.
+    pub fn biggest_wins(&self) -> HashSet<CategoryType> {
+        if self.disk_pressure() != DiskPressure::Critical {
+            return HashSet::new();
+        }
+        let mut results: Vec<&ScanResult> =
+            self.results.iter().filter(|r| r.total_size > 0).collect();
+        results.sort_by_key(|r| Reverse(r.total_size));
+        results
+            .into_iter()
+            .take(BIGGEST_WINS)
+            .map(|r| r.category)
+            .collect()
+    }
+
+    /// Formats a byte count in the preferred units.
+    pub fn fmt_size(&self, bytes: u64) -> String {
+        self.units.format(bytes)
+    }
+
     pub fn next(&mut self) {
         if self.results.is_empty() {
             return;
@@ -69,6 +395,7 @@ impl App {
             None => 0,
         };
         self.list_state.select(Some(i));
+        self.details_scroll = 0;
     }
 
     pub fn previous(&mut self) {
@@ -87,13 +414,265 @@ impl App {
             None => 0,
         };
         self.list_state.select(Some(i));
+        self.details_scroll = 0;
+    }
+
+    /// Switches the navigation keys between the categories and the details pane.
+    pub fn toggle_focus(&mut self) {
+        self.focus = match self.focus {
+            Focus::Categories => Focus::Details,
+            Focus::Details => Focus::Categories,
+        };
+    }
+
+    /// Moves down in the focused pane.
+    pub fn navigate_down(&mut self) {
+        match self.focus {
+            Focus::Categories => self.next(),
+            Focus::Details => self.scroll_details_down(),
+        }
+    }
+
+    /// Moves up in the focused pane.
+    pub fn navigate_up(&mut self) {
+        match self.focus {
+            Focus::Categories => self.previous(),
+            Focus::Details => self.scroll_details_up(),
+        }
     }
 
     pub fn toggle(&mut self) {
         if let Some(i) = self.list_state.selected()
             && i < self.results.len()
         {
-            self.results[i].is_selected = !self.results[i].is_selected;
+            self.set_selected(i, !self.results[i].is_selected);
+        }
+    }
+
+    /// Selects or deselects a category, leaving out the items it keeps by default.
+    fn set_selected(&mut self, index: usize, selected: bool) {
+        let kept = if selected {
+            self.kept_items(&self.results[index])
+        } else {
+            HashSet::new()
+        };
+        let result = &mut self.results[index];
+        result.is_selected = selected;
+        for item in &result.items {
+            self.excluded.remove(&item.path);
+        }
+        self.excluded.extend(kept);
+    }
+
+    /// Items of a category that stay unselected when it is selected: recent
+    /// downloads, each project's newest Xcode `DerivedData`, the newest items
+    /// the category keeps (`keep_recent`) and caches in use.
+    pub fn kept_items(&self, result: &ScanResult) -> HashSet<PathBuf> {
+        let mut kept = match result.category {
+            CategoryType::Downloads => result
+                .items
+                .iter()
+                .filter(|item| self.is_recent_download(result.category, item))
+                .map(|item| item.path.clone())
+                .collect(),
+            CategoryType::XcodeJunk if self.keep_latest_derived_data => {
+                xcode::latest_derived_data(&result.items)
+            }
+            _ => HashSet::new(),
+        };
+        if let Some(&count) = self.keep_recent.get(&result.category) {
+            kept.extend(newest_items(&result.items, count));
+        }
+        kept.extend(result.in_use.iter().cloned());
+        kept
+    }
+
+    /// Whether an item is a Downloads file too new to be selected by default.
+    pub fn is_recent_download(&self, category: CategoryType, item: &ScannedItem) -> bool {
+        category == CategoryType::Downloads
+            && item.is_recent(self.downloads_min_age, SystemTime::now())
+    }
+
+    /// Items of a category that cleaning it would remove.
+    fn included_items<'a>(
+        &'a self,
+        result: &'a ScanResult,
+    ) -> impl Iterator<Item = &'a ScannedItem> {
+        result
+            .items
+            .iter()
+            .filter(|item| !self.excluded.contains(&item.path))
+    }
+
+    pub fn scroll_details_down(&mut self) {
+        if let Some(result) = self.list_state.selected().and_then(|i| self.results.get(i))
+            && self.details_scroll + 1 < self.detail_items(result).len()
+        {
+            self.details_scroll += 1;
+        }
+    }
+
+    /// Items listed in the details pane for `result`, honoring the installer
+    /// filter and the item sort.
+    pub fn detail_items<'a>(&self, result: &'a ScanResult) -> Vec<&'a ScannedItem> {
+        let mut items: Vec<&ScannedItem> = result
+            .items
+            .iter()
+            .filter(|item| !self.installers_only || item.is_installer())
+            .collect();
+        self.item_sort.sort(&mut items);
+        items
+    }
+
+    /// Switches the details pane between sorting by size, age and name.
+    pub fn cycle_item_sort(&mut self) {
+        self.item_sort = self.item_sort.next();
+        self.details_scroll = 0;
+    }
+
+    /// Sorts categories by size, largest first, with pinned categories on top.
+    pub fn apply_sort(&mut self) {
+        let pinned = &self.pinned;
+        self.results
+            .sort_by_key(|r| (!pinned.contains(&r.category), Reverse(r.total_size)));
+    }
+
+    /// Pins or unpins the highlighted category, which stays highlighted as it moves.
+    pub fn toggle_pin(&mut self) {
+        let Some(category) = self
+            .list_state
+            .selected()
+            .and_then(|i| self.results.get(i))
+            .map(|r| r.category)
+        else {
+            return;
+        };
+        if !self.pinned.remove(&category) {
+            self.pinned.insert(category);
+        }
+        self.apply_sort();
+        let index = self.results.iter().position(|r| r.category == category);
+        self.list_state.select(index);
+        pins::save(&self.pinned);
+    }
+
+    /// Expands the item at the top of the details pane into its immediate
+    /// children, or collapses it again. The children are listed in the
+    /// background; pressing `e` again while they are stops the listing.
+    pub fn toggle_expand(&mut self) {
+        let Some(result) = self.list_state.selected().and_then(|i| self.results.get(i)) else {
+            return;
+        };
+        let Some(path) = self
+            .detail_items(result)
+            .get(self.details_scroll)
+            .map(|item| item.path.clone())
+        else {
+            return;
+        };
+        if let Some(expansion) = self.expanding.take() {
+            expansion.cancel.store(true, Ordering::Relaxed);
+            if expansion.path == path {
+                return;
+            }
+        }
+        if self.expanded.remove(&path).is_some() {
+            return;
+        }
+        if !path.is_dir() {
+            self.message = Some("Only folders can be expanded".to_string());
+            return;
+        }
+
+        let cancel = Arc::new(AtomicBool::new(false));
+        let options = ScanOptions {
+            cancelled: Arc::clone(&cancel),
+            ..(*self.scan_options).clone()
+        };
+        let (tx, rx) = mpsc::channel();
+        let dir = path.clone();
+        thread::spawn(move || {
+            let (_, children) = scanner::utils::scan_path(&dir, None, &options);
+            let _ = tx.send(children);
+        });
+        self.expanding = Some(Expansion { path, cancel, rx });
+    }
+
+    /// Lists the children of the expanded item once the background listing is done.
+    pub fn check_expand_status(&mut self) {
+        let Some(expansion) = &self.expanding else {
+            return;
+        };
+        match expansion.rx.try_recv() {
+            Ok(children) => {
+                if let Some(expansion) = self.expanding.take() {
+                    self.expanded.insert(expansion.path, children);
+                }
+            }
+            Err(mpsc::TryRecvError::Disconnected) => self.expanding = None,
+            Err(mpsc::TryRecvError::Empty) => {}
+        }
+    }
+
+    pub fn toggle_installers_only(&mut self) {
+        self.installers_only = !self.installers_only;
+        self.details_scroll = 0;
+    }
+
+    pub fn scroll_details_up(&mut self) {
+        self.details_scroll = self.details_scroll.saturating_sub(1);
+    }
+
+    /// Maps a terminal coordinate to the index of the category row under it.
+    pub fn category_row_at(&self, column: u16, row: u16) -> Option<usize> {
+        let area = self.list_area;
+        // Exclude the rounded border around the list
+        let inner = Rect::new(
+            area.x + 1,
+            area.y + 1,
+            area.width.saturating_sub(2),
+            area.height.saturating_sub(2),
+        );
+        if !inner.contains(Position::new(column, row)) {
+            return None;
+        }
+
+        let index = self.list_state.offset() + usize::from(row - inner.y);
+        (index < self.results.len()).then_some(index)
+    }
+
+    pub fn handle_mouse(&mut self, mouse: MouseEvent) {
+        let position = Position::new(mouse.column, mouse.row);
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                if let Some(index) = self.category_row_at(mouse.column, mouse.row) {
+                    self.focus = Focus::Categories;
+                    if self.list_state.selected() != Some(index) {
+                        self.list_state.select(Some(index));
+                        self.details_scroll = 0;
+                    }
+
+                    let checkbox_start = self.list_area.x + 1 + HIGHLIGHT_SYMBOL_WIDTH;
+                    if (checkbox_start..checkbox_start + CHECKBOX_WIDTH).contains(&mouse.column) {
+                        self.toggle();
+                    }
+                }
+            }
+            MouseEventKind::ScrollDown => {
+                if self.details_area.contains(position) {
+                    self.scroll_details_down();
+                } else if self.list_area.contains(position) {
+                    self.next();
+                }
+            }
+            MouseEventKind::ScrollUp => {
+                if self.details_area.contains(position) {
+                    self.scroll_details_up();
+                } else if self.list_area.contains(position) {
+                    self.previous();
+                }
+            }
+            _ => {}
         }
     }
 
@@ -102,55 +681,460 @@ impl App {
             return;
         }
         let all_selected = self.results.iter().all(|r| r.is_selected);
-        for result in &mut self.results {
-            result.is_selected = !all_selected;
+        for i in 0..self.results.len() {
+            self.set_selected(i, !all_selected);
         }
     }
 
+    /// Everything found, selected or not.
+    pub fn total_size_all(&self) -> u64 {
+        self.results.iter().map(|r| r.total_size).sum()
+    }
+
     pub fn total_selected_size(&self) -> u64 {
         self.results
             .iter()
             .filter(|r| r.is_selected)
-            .map(|r| r.total_size)
+            .map(|r| {
+                let excluded: u64 = r
+                    .items
+                    .iter()
+                    .filter(|item| self.excluded.contains(&item.path))
+                    .map(|item| item.size)
+                    .sum();
+                r.total_size.saturating_sub(excluded)
+            })
             .sum()
     }
 
-    pub fn clean_selected(&mut self) {
-        // Collect all items to delete
-        let mut items_to_delete = Vec::new();
-        for result in &self.results {
-            if result.is_selected {
-                items_to_delete.extend(result.items.clone());
+    pub fn toggle_delete_mode(&mut self) {
+        self.delete_mode = self.delete_mode.toggled();
+    }
+
+    pub fn is_trash_selected(&self) -> bool {
+        self.results
+            .iter()
+            .any(|r| r.is_selected && r.category == CategoryType::Trash)
+    }
+
+    /// Items that confirming would clean: the staged plan, or else the selected categories.
+    pub fn planned_batches(&self) -> Batches {
+        self.plan.clone().unwrap_or_else(|| self.selected_batches())
+    }
+
+    pub fn planned_size(&self) -> u64 {
+        match &self.plan {
+            Some(plan) => plan
+                .iter()
+                .flat_map(|(_, items)| items)
+                .map(|i| i.size)
+                .sum(),
+            None => self.total_selected_size(),
+        }
+    }
+
+    /// How many items confirming would remove.
+    pub fn planned_count(&self) -> usize {
+        self.planned_batches()
+            .iter()
+            .map(|(_, items)| items.len())
+            .sum()
+    }
+
+    /// The size and item count of the plan, e.g. "2.9 GB across 1,204 items".
+    pub fn planned_total(&self) -> String {
+        let count = self.planned_count();
+        format!(
+            "{} across {} {}",
+            self.fmt_size(self.planned_size()),
+            units::format_count(count),
+            if count == 1 { "item" } else { "items" }
+        )
+    }
+
+    pub fn is_trash_planned(&self) -> bool {
+        match &self.plan {
+            Some(plan) => plan.iter().any(|(c, _)| *c == CategoryType::Trash),
+            None => self.is_trash_selected(),
+        }
+    }
+
+    /// One line per planned category with its size, followed by the total.
+    pub fn plan_summary(&self) -> String {
+        let mut summary = String::new();
+        for (category, items) in self.planned_batches() {
+            let size: u64 = items.iter().map(|i| i.size).sum();
+            let _ = writeln!(
+                summary,
+                "{:<22} {:>10}",
+                category.name(),
+                self.fmt_size(size)
+            );
+        }
+        let _ = write!(
+            summary,
+            "{:<22} {:>10}",
+            "Total",
+            self.fmt_size(self.planned_size())
+        );
+        summary
+    }
+
+    /// How confirming would remove the planned items under the current delete mode.
+    pub fn clean_plan(&self) -> CleanPlan {
+        CleanPlan::new(&self.planned_batches(), self.delete_mode)
+            .with_destination(self.move_to.clone())
+    }
+
+    /// How cleaning removes items: the delete mode, or the `--move-to` folder.
+    pub fn mode_label(&self) -> String {
+        match &self.move_to {
+            Some(dir) => format!("Move to {}", dir.display()),
+            None => self.delete_mode.label().to_string(),
+        }
+    }
+
+    /// Warns when the plan includes system locations that need `sudo`,
+    /// counting the items that will likely be skipped.
+    pub fn privileged_warning(&self) -> Option<String> {
+        if self.is_root {
+            return None;
+        }
+        let count = self
+            .planned_batches()
+            .iter()
+            .flat_map(|(_, items)| items)
+            .filter(|item| cleaner::is_privileged_path(&item.path))
+            .count();
+        (count > 0).then(|| {
+            format!(
+                "{count} items are in system folders that need sudo and will likely be skipped."
+            )
+        })
+    }
+
+    /// How much of the plan will likely be freed, and how many items will likely
+    /// be skipped: those in system folders without root, and those allowlisted
+    /// since the scan, which [`App::clean_selected`] leaves out.
+    pub fn net_estimate(&self) -> (u64, usize) {
+        let skipped: Vec<u64> = self
+            .planned_batches()
+            .iter()
+            .flat_map(|(_, items)| items)
+            .filter(|item| {
+                (!self.is_root && cleaner::is_privileged_path(&item.path))
+                    || self.allowlist.is_allowed(&item.path)
+            })
+            .map(|item| item.size)
+            .collect();
+        let skipped_size: u64 = skipped.iter().sum();
+        (
+            self.planned_size().saturating_sub(skipped_size),
+            skipped.len(),
+        )
+    }
+
+    /// Asks to confirm cleaning the plan (or the selection), reading the
+    /// allowlist again so the estimate covers rules added since the scan.
+    pub fn confirm(&mut self) {
+        self.allowlist = Allowlist::load();
+        self.state = AppState::Confirming;
+    }
+
+    /// Whether `q` may quit right away. With categories selected it asks first,
+    /// so a careful selection isn't discarded by accident.
+    pub fn request_quit(&mut self) -> bool {
+        if self.total_selected_size() > 0 {
+            self.state = AppState::ConfirmQuit;
+            false
+        } else {
+            true
+        }
+    }
+
+    pub fn cancel_confirm(&mut self) {
+        self.plan = None;
+        self.review_index = 0;
+        self.state = AppState::Browsing;
+    }
+
+    /// Moves the highlight in the confirmation popup by `delta` categories.
+    pub fn move_review(&mut self, delta: isize) {
+        let last = self.planned_batches().len().saturating_sub(1);
+        self.review_index = self.review_index.saturating_add_signed(delta).min(last);
+    }
+
+    /// Drops the highlighted category from the plan without touching the
+    /// selections, going back to browsing once nothing is left.
+    pub fn remove_from_plan(&mut self) {
+        let mut batches = self.planned_batches();
+        if self.review_index >= batches.len() {
+            return;
+        }
+        batches.remove(self.review_index);
+        if batches.is_empty() {
+            self.cancel_confirm();
+            return;
+        }
+        self.review_index = self.review_index.min(batches.len() - 1);
+        self.plan = Some(batches);
+    }
+
+    /// The `limit` largest items across all categories, biggest first.
+    pub fn top_items(&self, limit: usize) -> Vec<(CategoryType, &ScannedItem)> {
+        let mut items: Vec<(CategoryType, &ScannedItem)> = self
+            .results
+            .iter()
+            .flat_map(|r| r.items.iter().map(move |item| (r.category, item)))
+            .collect();
+        items.sort_by_key(|(_, item)| Reverse(item.size));
+        items.truncate(limit);
+        items
+    }
+
+    pub fn toggle_view(&mut self) {
+        self.view = match self.view {
+            View::Categories => View::TopItems,
+            View::TopItems => View::Categories,
+        };
+        if self.view == View::TopItems && self.top_state.selected().is_none() {
+            self.top_state.select(Some(0));
+        }
+    }
+
+    pub fn next_top_item(&mut self) {
+        let len = self.top_items(TOP_ITEMS_LIMIT).len();
+        if len > 0 {
+            let i = self.top_state.selected().map_or(0, |i| (i + 1) % len);
+            self.top_state.select(Some(i));
+        }
+    }
+
+    pub fn previous_top_item(&mut self) {
+        let len = self.top_items(TOP_ITEMS_LIMIT).len();
+        if len > 0 {
+            let i = self
+                .top_state
+                .selected()
+                .map_or(0, |i| if i == 0 { len - 1 } else { i - 1 });
+            self.top_state.select(Some(i));
+        }
+    }
+
+    /// The path under the cursor: the highlighted top item, or the highlighted category's root.
+    fn highlighted_path(&self) -> Option<&Path> {
+        match self.view {
+            View::TopItems => self.top_state.selected().and_then(|i| {
+                self.top_items(TOP_ITEMS_LIMIT)
+                    .get(i)
+                    .map(|(_, item)| item.path.as_path())
+            }),
+            View::Categories => self
+                .list_state
+                .selected()
+                .and_then(|i| self.results.get(i))
+                .map(|r| r.root_path.as_path()),
+        }
+    }
+
+    /// Reveals the highlighted path in Finder.
+    pub fn reveal_highlighted(&mut self) {
+        let Some(path) = self.highlighted_path() else {
+            return;
+        };
+        let Some(args) = reveal_args(path, path.is_dir()) else {
+            self.message = Some("Container images can't be shown in Finder".to_string());
+            return;
+        };
+        if let Err(e) = Command::new("open").args(args).spawn() {
+            self.message = Some(format!("Failed to open Finder: {e}"));
+        }
+    }
+
+    /// Copies the highlighted path, or a container image's ID, to the clipboard.
+    pub fn copy_highlighted(&mut self) {
+        let Some(path) = self.highlighted_path() else {
+            return;
+        };
+        let what = if ContainerEngine::parse_path(path).is_some() {
+            "image ID"
+        } else {
+            "path"
+        };
+        self.message = Some(match copy_to_clipboard(&clipboard_text(path)) {
+            Ok(()) => format!("Copied {what} to clipboard"),
+            Err(e) => format!("Failed to copy to the clipboard: {e}"),
+        });
+    }
+
+    /// Stages just the highlighted top item for cleaning and asks for confirmation.
+    pub fn stage_top_item(&mut self) {
+        let Some(index) = self.top_state.selected() else {
+            return;
+        };
+        let top = self.top_items(TOP_ITEMS_LIMIT);
+        if let Some((category, item)) = top.get(index) {
+            self.plan = Some(vec![(*category, vec![(*item).clone()])]);
+            self.confirm();
+        }
+    }
+
+    /// Stages only the highlighted category for confirmation, leaving every
+    /// category's selection untouched.
+    pub fn stage_highlighted_category(&mut self) {
+        let Some(result) = self.list_state.selected().and_then(|i| self.results.get(i)) else {
+            return;
+        };
+        let category = result.category;
+        let items: Vec<ScannedItem> = if result.is_selected {
+            self.included_items(result).cloned().collect()
+        } else {
+            let kept = self.kept_items(result);
+            result
+                .items
+                .iter()
+                .filter(|item| !kept.contains(&item.path))
+                .cloned()
+                .collect()
+        };
+        if !items.is_empty() {
+            self.plan = Some(vec![(category, items)]);
+            self.confirm();
+        }
+    }
+
+    /// Selects the largest, lowest-risk categories until `target_bytes` are covered.
+    /// High-risk categories are never picked. Returns whether the target was reached.
+    pub fn auto_select(&mut self, target_bytes: u64) -> bool {
+        for i in 0..self.results.len() {
+            self.set_selected(i, false);
+        }
+
+        let mut candidates: Vec<usize> = (0..self.results.len())
+            .filter(|&i| {
+                let r = &self.results[i];
+                r.total_size > 0 && r.category.risk() != Risk::High
+            })
+            .collect();
+        candidates.sort_by_key(|&i| {
+            let r = &self.results[i];
+            (r.category.risk(), Reverse(r.total_size))
+        });
+
+        for i in candidates {
+            if self.total_selected_size() >= target_bytes {
+                break;
             }
+            self.set_selected(i, true);
         }
+        self.total_selected_size() >= target_bytes
+    }
 
-        if items_to_delete.is_empty() {
+    /// Selects every non-empty category for an unattended clean (`--yes`).
+    /// Categories riskier than `max_risk` are left out and returned so the
+    /// caller can say why.
+    pub fn select_unattended(&mut self, max_risk: Risk) -> Vec<CategoryType> {
+        let mut refused = Vec::new();
+        for i in 0..self.results.len() {
+            let result = &self.results[i];
+            let allowed = result.category.risk() <= max_risk;
+            if !allowed && !result.items.is_empty() {
+                refused.push(result.category);
+            }
+            self.set_selected(i, allowed && !result.items.is_empty());
+        }
+        refused
+    }
+
+    /// Items of the selected categories, grouped by category.
+    pub fn selected_batches(&self) -> Vec<(CategoryType, Vec<ScannedItem>)> {
+        self.results
+            .iter()
+            .filter(|r| r.is_selected)
+            .map(|r| {
+                (
+                    r.category,
+                    self.included_items(r).cloned().collect::<Vec<_>>(),
+                )
+            })
+            .filter(|(_, items)| !items.is_empty())
+            .collect()
+    }
+
+    /// Batches without the items allowlisted since the scan.
+    fn without_allowlisted(&self, batches: Batches) -> Batches {
+        batches
+            .into_iter()
+            .map(|(category, mut items)| {
+                items.retain(|item| !self.allowlist.is_allowed(&item.path));
+                (category, items)
+            })
+            .filter(|(_, items)| !items.is_empty())
+            .collect()
+    }
+
+    pub fn clean_selected(&mut self) {
+        // Collect all items to delete, grouped by category
+        let batches = self.without_allowlisted(self.planned_batches());
+        self.plan = None;
+        self.review_index = 0;
+        self.denied.clear();
+
+        if batches.is_empty() {
             self.state = AppState::Done("Nothing selected to clean.".to_string());
             return;
         }
+        // Refuse up front rather than after some categories were moved
+        if let Err(e) = CleanPlan::new(&batches, self.delete_mode)
+            .with_destination(self.move_to.clone())
+            .with_units(self.units)
+            .ensure_room()
+        {
+            self.state = AppState::Done(e.to_string());
+            return;
+        }
 
         self.state = AppState::Cleaning;
+        self.cleaning_batches.clone_from(&batches);
 
         // Threaded cleaning
         let (tx, rx) = mpsc::channel();
         self.cleaning_rx = Some(rx);
 
         // Move items to a separate thread
-        let items = items_to_delete;
+        let mode = self.delete_mode;
+        let move_to = self.move_to.clone();
+        let units = self.units;
+        let stop = Arc::clone(&self.interrupted);
         thread::spawn(move || {
-            // Artificial delay to make "Cleaning" state visible if it's too fast?
-            // thread::sleep(Duration::from_millis(500));
-
-            let size = items.iter().map(|i| i.size).sum::<u64>();
-            match cleaner::delete_items(&items) {
-                Ok(()) => {
-                    let msg = format!("Successfully cleaned {}!", format_size(size, BINARY));
-                    let _ = tx.send(Ok(msg));
-                }
-                Err(e) => {
-                    let _ = tx.send(Err(format!("Error during cleaning: {e}")));
+            let mut size = 0;
+            let mut report = CleanReport::default();
+            for (category, items) in &batches {
+                let plan = CleanPlan::new(&[(*category, items.clone())], mode)
+                    .with_destination(move_to.clone())
+                    .with_stop(Arc::clone(&stop))
+                    .with_units(units);
+                match plan.execute(false, None) {
+                    Ok(mut batch) => {
+                        size += batch.freed(items);
+                        report.append(&mut batch);
+                    }
+                    Err(e) => {
+                        undo::record_last_clean(report.trashed);
+                        let _ = tx.send(Err(format!("Error during cleaning: {e}")));
+                        return;
+                    }
                 }
             }
+            undo::record_last_clean(std::mem::take(&mut report.trashed));
+
+            let lifetime = stats::record_freed(size).reclaimed;
+            let msg = format!(
+                "Successfully cleaned {}!\nLifetime reclaimed: {}",
+                units.format(size),
+                units.format(lifetime)
+            );
+            let _ = tx.send(Ok((msg, report)));
         });
     }
 
@@ -159,33 +1143,284 @@ impl App {
             && let Ok(result) = rx.try_recv()
         {
             match result {
-                Ok(msg) => {
-                    self.state = AppState::Done(msg);
-                    // Clear selection and items (naive update)
-                    for result in &mut self.results {
-                        if result.is_selected {
-                            result.is_selected = false;
-                            result.items.clear();
-                            result.total_size = 0;
-                        }
+                Ok((mut msg, report)) => {
+                    let batches = std::mem::take(&mut self.cleaning_batches);
+                    let cleaned_categories: Vec<CategoryType> =
+                        batches.iter().map(|(category, _)| *category).collect();
+                    let cleaned: HashSet<PathBuf> = batches
+                        .into_iter()
+                        .flat_map(|(_, items)| items)
+                        .map(|item| item.path)
+                        .filter(|path| !report.left_behind(path))
+                        .collect();
+                    let CleanReport {
+                        denied,
+                        in_use,
+                        skipped,
+                        ..
+                    } = report;
+                    self.remove_cleaned(&cleaned);
+                    for category in cleaned_categories {
+                        self.rescan_category(category);
+                    }
+                    if !in_use.is_empty() {
+                        let _ = write!(
+                            msg,
+                            "\n\n{} items are in use by running apps and were left behind.",
+                            in_use.len()
+                        );
                     }
+                    if !skipped.is_empty() {
+                        let _ = write!(
+                            msg,
+                            "\n\nInterrupted: {} items were left untouched.",
+                            skipped.len()
+                        );
+                    }
+                    self.state = AppState::Done(if denied.is_empty() {
+                        msg
+                    } else if self.move_to.is_some() {
+                        // Retrying runs `rm -rf`, which must not stand in for a move
+                        format!(
+                            "{msg}\n\n{} items couldn't be moved for lack of permissions.",
+                            denied.len()
+                        )
+                    } else if self.delete_mode == DeleteMethod::Trash {
+                        // Retrying runs `rm -rf`, so say the Trash is skipped
+                        format!(
+                            "{msg}\n\n{} items couldn't be moved to the Trash without \
+                             administrator rights. Press [r] to delete them permanently \
+                             with administrator privileges (macOS will ask for your password).",
+                            denied.len()
+                        )
+                    } else {
+                        format!(
+                            "{msg}\n\n{} items need administrator rights. \
+                             Press [r] to retry them with administrator privileges \
+                             (macOS will ask for your password).",
+                            denied.len()
+                        )
+                    });
+                    if self.move_to.is_none() {
+                        self.denied = denied;
+                    }
+
+                    // Refresh disk info after cleaning
+                    self.refresh_disks(Instant::now());
+                }
+                Err(err_msg) => {
+                    self.state = AppState::Done(err_msg);
+                    self.cleaning_batches.clear();
+                    self.denied.clear();
+                }
+            }
+            self.cleaning_rx = None; // Detach receiver
+            // A Ctrl-C during the clean has been honored by stopping it; stay on
+            // the Done screen so it can say what was left, and quit on the next one
+            self.interrupted.store(false, Ordering::Relaxed);
+        }
+    }
+
+    /// Drops cleaned items from the results and deselects categories left empty.
+    fn remove_cleaned(&mut self, cleaned: &HashSet<PathBuf>) {
+        for result in &mut self.results {
+            let before = result.items.len();
+            result.items.retain(|item| !cleaned.contains(&item.path));
+            if result.items.len() == before {
+                continue;
+            }
+            result.total_size = result.items.iter().map(|i| i.size).sum();
+            if result.items.is_empty() {
+                result.is_selected = false;
+            }
+        }
+        self.results_changed();
+    }
+
+    /// Keeps the view consistent with `results` after they changed: the
+    /// selection stays in range and expanded items, now stale, collapse.
+    fn results_changed(&mut self) {
+        self.clamp_selection();
+        self.expanded.clear();
+        if let Some(expansion) = self.expanding.take() {
+            expansion.cancel.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Keeps the highlighted category within `results`, or clears it when
+    /// there are none. Called whenever `results` changes.
+    pub fn clamp_selection(&mut self) {
+        let last = self.results.len().checked_sub(1);
+        let selected = self
+            .list_state
+            .selected()
+            .zip(last)
+            .map(|(i, last)| i.min(last));
+        self.list_state.select(selected);
+    }
+
+    /// Re-runs the scanner of `category` in the background so its numbers
+    /// reflect what is left on disk; the result replaces the current one.
+    pub fn rescan_category(&mut self, category: CategoryType) {
+        match scanner::scanner_for(category, &self.scan_options) {
+            Ok(Some(scanner)) => self.rescan_with(scanner),
+            Ok(None) => {}
+            Err(e) => log::warn!("Can't rescan {}: {e}", category.name()),
+        }
+    }
+
+    fn rescan_with(&mut self, scanner: Box<dyn scanner::Scanner>) {
+        self.pending_rescans += 1;
+        let tx = self.rescan_tx.clone();
+        // A flag of its own, so stopping the first scan doesn't cut rescans short
+        let options = ScanOptions {
+            cancelled: Arc::default(),
+            ..(*self.scan_options).clone()
+        };
+        thread::spawn(move || {
+            let _ = tx.send(scanner::run_scanner(scanner.as_ref(), None, &options));
+        });
+    }
+
+    /// Swaps in results of finished re-scans, keeping each category's selection.
+    pub fn check_rescan_status(&mut self) {
+        let mut changed = false;
+        while let Ok(mut fresh) = self.rescan_rx.try_recv() {
+            self.pending_rescans = self.pending_rescans.saturating_sub(1);
+            let Some(index) = self
+                .results
+                .iter()
+                .position(|r| r.category == fresh.category)
+            else {
+                continue;
+            };
+            let current = &self.results[index];
+            fresh.is_selected = current.is_selected && !fresh.items.is_empty();
+            if fresh.is_selected {
+                // Keep what the policies protect now (new recent downloads, caches
+                // opened since), but leave earlier choices as the user made them
+                let kept_before = self.kept_items(current);
+                let newly_kept: Vec<PathBuf> = self
+                    .kept_items(&fresh)
+                    .into_iter()
+                    .filter(|path| !kept_before.contains(path))
+                    .collect();
+                self.excluded.extend(newly_kept);
+            }
+            self.results[index] = fresh;
+            changed = true;
+        }
+        if changed {
+            self.results_changed();
+        }
+    }
+
+    /// Whether `--watch` is due to re-scan. Refreshes wait while a clean is being
+    /// confirmed or run, and until the previous refresh has come back.
+    pub fn should_refresh(&self, now: Instant) -> bool {
+        self.watch_interval.is_some_and(|interval| {
+            matches!(self.state, AppState::Browsing)
+                && self.pending_rescans == 0
+                && now.saturating_duration_since(self.last_refresh) >= interval
+        })
+    }
+
+    /// Whether the free space shown in the header is due to be read again, as
+    /// other apps may have freed or used space in the meantime.
+    pub fn should_refresh_disks(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.last_disk_refresh)
+            >= Duration::from_secs(DISK_REFRESH_SECS)
+    }
+
+    /// Reads the free space of the disks again.
+    pub fn refresh_disks(&mut self, now: Instant) {
+        self.last_disk_refresh = now;
+        self.disks.refresh(true);
+    }
+
+    /// Re-scans every category in the background. Results are swapped in by
+    /// [`App::check_rescan_status`], keeping selections and the scroll position.
+    pub fn refresh_all(&mut self, now: Instant) {
+        self.last_refresh = now;
+        match scanner::get_all_scanners(&self.scan_options) {
+            Ok(scanners) => {
+                for scanner in scanners {
+                    self.rescan_with(scanner);
+                }
+            }
+            Err(e) => log::warn!("Can't refresh: {e}"),
+        }
+    }
+
+    /// Deletes the paths the last clean was denied, as root via an admin prompt.
+    /// Runs on the cleaning thread, as the prompt waits for the user.
+    pub fn retry_denied(&mut self) {
+        let denied = std::mem::take(&mut self.denied);
+        if denied.is_empty() {
+            return;
+        }
+        let paths: HashSet<&PathBuf> = denied.iter().collect();
+        self.cleaning_batches = self
+            .results
+            .iter()
+            .map(|r| {
+                let items: Vec<ScannedItem> = r
+                    .items
+                    .iter()
+                    .filter(|item| paths.contains(&item.path))
+                    .cloned()
+                    .collect();
+                (r.category, items)
+            })
+            .filter(|(_, items)| !items.is_empty())
+            .collect();
+        self.state = AppState::Cleaning;
+
+        let (tx, rx) = mpsc::channel();
+        self.cleaning_rx = Some(rx);
+        thread::spawn(move || {
+            let outcome = match cleaner::delete_with_admin_privileges(&denied) {
+                Ok(()) => Ok((
+                    format!(
+                        "Removed {} items with administrator privileges.",
+                        denied.len()
+                    ),
+                    CleanReport::default(),
+                )),
+                Err(e) => Err(format!("Retry failed: {e}")),
+            };
+            let _ = tx.send(outcome);
+        });
+    }
+
+    /// Leaves the Done screen. Denied paths are only offered for a retry once.
+    pub fn dismiss_done(&mut self) {
+        self.denied.clear();
+        self.state = AppState::Browsing;
+    }
 
-                    // Refresh disk info after cleaning
-                    self.disks.refresh(true);
-                }
-                Err(err_msg) => {
-                    self.state = AppState::Done(err_msg);
-                }
-            }
-            self.cleaning_rx = None; // Detach receiver
-        }
+    /// Moves the files of the last clean back out of the Trash.
+    pub fn undo_last_clean(&mut self) {
+        self.denied.clear();
+        self.state = AppState::Done(match undo::undo_last_clean() {
+            Ok(0) => "Nothing to undo.".to_string(),
+            Ok(count) => format!("Restored {count} items from the Trash."),
+            Err(e) => format!("Undo failed: {e}"),
+        });
     }
+
     pub fn start_scan(&mut self) {
         let (tx, rx) = mpsc::channel();
         self.scan_rx = Some(rx);
 
-        let allowlist = Arc::new(Allowlist::load());
-        let scanners = scanner::get_all_scanners();
+        let scanners = match scanner::get_all_scanners(&self.scan_options) {
+            Ok(scanners) => scanners,
+            Err(e) => {
+                self.scan_rx = None;
+                self.state = AppState::Done(e.to_string());
+                return;
+            }
+        };
 
         self.total_categories = scanners.len();
 
@@ -198,34 +1433,111 @@ impl App {
                 ScanProgress {
                     category,
                     items_count: 0,
+                    items_total: 0,
+                    bytes: 0,
                     status: "Waiting...".to_string(),
+                    duration: Duration::ZERO,
                 },
             );
 
             let tx_clone = tx.clone();
-            let allowlist_clone = Arc::clone(&allowlist);
+            let options = Arc::clone(&self.scan_options);
+            let units = self.units;
 
             thread::spawn(move || {
                 let cat_name = category; // copy
 
-                // Progress callback
+                // Progress callback, sent as deltas accumulated in the main thread
+                let throttle = Arc::new(ProgressThrottle::new(
+                    cat_name,
+                    Duration::from_millis(PROGRESS_INTERVAL_MS),
+                    Instant::now(),
+                ));
                 let tx_progress = tx_clone.clone();
-                let cb = move || {
-                    let _ = tx_progress.send(ScanUpdate::Progress(ScanProgress {
-                        category: cat_name,
-                        items_count: 1, // This will need to be accumulated in the main thread
-                        status: "Scanning...".to_string(),
-                    }));
+                let pending = Arc::clone(&throttle);
+                let cb = move |event: ProgressEvent| {
+                    if let Some(progress) = pending.record(event, Instant::now()) {
+                        let _ = tx_progress.send(ScanUpdate::Progress(progress));
+                    }
                 };
 
                 // Perform scan
-                let res = scanner.scan(Some(&cb), &allowlist_clone);
+                let res = scanner::run_scanner(scanner.as_ref(), Some(&cb), &options);
+                if let Some(progress) = throttle.flush() {
+                    let _ = tx_clone.send(ScanUpdate::Progress(progress));
+                }
+                info!(
+                    "Scanned {}: {} in {} items ({:.1}s)",
+                    cat_name.name(),
+                    units.format(res.total_size),
+                    res.items.len(),
+                    res.duration.as_secs_f64()
+                );
 
                 let _ = tx_clone.send(ScanUpdate::Result(res));
             });
         }
     }
 
+    /// Hashes the large files of the results in the background, as reading
+    /// them can take a while. [`App::check_duplicates_status`] picks up the result.
+    fn start_finding_duplicates(&mut self) {
+        let items: Vec<ScannedItem> = self
+            .results
+            .iter()
+            .flat_map(|r| &r.items)
+            .filter(|item| item.size >= DUPLICATE_MIN_SIZE)
+            .cloned()
+            .collect();
+        let (tx, rx) = mpsc::channel();
+        self.duplicates_rx = Some(rx);
+        thread::spawn(move || {
+            let found = duplicates::find_duplicates(&items, DUPLICATE_MIN_SIZE);
+            let _ = tx.send(found.into_iter().flatten().collect());
+        });
+    }
+
+    /// Swaps in the duplicates once the background hashing is done.
+    pub fn check_duplicates_status(&mut self) {
+        if let Some(rx) = &self.duplicates_rx
+            && let Ok(duplicates) = rx.try_recv()
+        {
+            self.duplicates = duplicates;
+            self.duplicates_rx = None;
+        }
+    }
+
+    /// Overall scan completion, counting partial progress of running categories.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn scan_ratio(&self) -> f64 {
+        if self.total_categories == 0 {
+            return 0.0;
+        }
+        let completed: f64 = self.scan_progress.values().map(ScanProgress::ratio).sum();
+        (completed / self.total_categories as f64).clamp(0.0, 1.0)
+    }
+
+    /// Reacts to Ctrl-C by stopping a scan, and returns whether the app may quit.
+    /// A running clean finishes its current item first, so it is waited for.
+    pub fn handle_interrupt(&mut self) -> bool {
+        if !self.interrupted.load(Ordering::Relaxed) {
+            return false;
+        }
+        match self.state {
+            AppState::Scanning => {
+                self.cancel_scan();
+                true
+            }
+            AppState::Cleaning => false,
+            _ => true,
+        }
+    }
+
+    /// Stops the running scan; the categories report what they found so far.
+    pub fn cancel_scan(&mut self) {
+        self.scan_options.cancelled.store(true, Ordering::Relaxed);
+    }
+
     pub fn check_scan_status(&mut self) {
         if let Some(rx) = &self.scan_rx {
             // Non-blocking check for all available messages
@@ -234,12 +1546,16 @@ impl App {
                     ScanUpdate::Progress(progress) => {
                         if let Some(entry) = self.scan_progress.get_mut(&progress.category) {
                             entry.items_count += progress.items_count; // Aggregate counts
+                            entry.items_total += progress.items_total;
+                            entry.bytes += progress.bytes;
                             entry.status = progress.status;
                         }
                     }
                     ScanUpdate::Result(result) => {
                         if let Some(entry) = self.scan_progress.get_mut(&result.category) {
+                            entry.bytes = result.total_size;
                             entry.status = "Done".to_string();
+                            entry.duration = result.duration;
                         }
                         self.results.push(result);
                     }
@@ -248,13 +1564,26 @@ impl App {
 
             // Check if scanning is complete
             if self.results.len() == self.total_categories {
-                self.results.sort_by(|a, b| b.total_size.cmp(&a.total_size));
-
-                if !self.results.is_empty() {
-                    self.list_state.select(Some(0));
-                }
+                self.apply_sort();
+                self.list_state.select(Some(0));
+                self.results_changed();
                 self.state = AppState::Browsing;
                 self.scan_rx = None;
+                self.last_refresh = Instant::now();
+                if self.scan_options.is_cancelled() {
+                    self.message = Some("Scan cancelled: sizes may be incomplete.".to_string());
+                }
+
+                if self.find_duplicates {
+                    self.start_finding_duplicates();
+                }
+
+                if let Some(target) = self.auto_select_target.take() {
+                    self.auto_select(target);
+                    if self.total_selected_size() > 0 {
+                        self.confirm();
+                    }
+                }
             }
         }
     }
@@ -267,7 +1596,7 @@ mod tests {
 
     #[test]
     fn test_check_scan_status_updates() {
-        let mut app = App::new_scanning();
+        let mut app = App::new_scanning(ScanOptions::default());
 
         // Setup manual test state
         let category = CategoryType::XcodeJunk;
@@ -276,7 +1605,10 @@ mod tests {
             ScanProgress {
                 category,
                 items_count: 0,
+                items_total: 0,
+                bytes: 0,
                 status: "Waiting...".to_string(),
+                duration: Duration::ZERO,
             },
         );
         app.total_categories = 1;
@@ -289,7 +1621,10 @@ mod tests {
         tx.send(ScanUpdate::Progress(ScanProgress {
             category,
             items_count: 5,
+            items_total: 10,
+            bytes: 1024,
             status: "Scanning...".to_string(),
+            duration: Duration::ZERO,
         }))
         .unwrap();
 
@@ -302,7 +1637,10 @@ mod tests {
             .get(&category)
             .expect("Category should exist");
         assert_eq!(progress.items_count, 5);
+        assert_eq!(progress.items_total, 10);
+        assert_eq!(progress.bytes, 1024);
         assert_eq!(progress.status, "Scanning...");
+        assert!((app.scan_ratio() - 0.5).abs() < f64::EPSILON);
         assert!(matches!(app.state, AppState::Scanning));
 
         // 2. Send Result (Done)
@@ -311,8 +1649,14 @@ mod tests {
             total_size: 1024,
             items: vec![],
             is_selected: false,
-            description: "Test description".to_string(),
             root_path: PathBuf::from("/tmp"),
+            roots: Vec::new(),
+            timed_out: false,
+            duration: Duration::ZERO,
+            offloaded: 0,
+            warnings: Vec::new(),
+            note: None,
+            in_use: Vec::new(),
         };
         tx.send(ScanUpdate::Result(result)).unwrap();
 
@@ -325,6 +1669,7 @@ mod tests {
             .get(&category)
             .expect("Category should exist");
         assert_eq!(progress.status, "Done");
+        assert!((app.scan_ratio() - 1.0).abs() < f64::EPSILON);
 
         // Should transition to Browsing because results.len() (1) == total_categories (1)
         assert_eq!(app.results.len(), 1);
@@ -332,35 +1677,83 @@ mod tests {
         assert!(app.scan_rx.is_none());
     }
 
-    #[test]
-    fn test_toggle_all() {
-        let mut app = App {
-            results: vec![
-                ScanResult {
-                    category: CategoryType::XcodeJunk,
-                    total_size: 100,
-                    items: vec![],
-                    is_selected: false,
-                    description: String::new(),
-                    root_path: PathBuf::new(),
-                },
-                ScanResult {
-                    category: CategoryType::SystemLogs,
-                    total_size: 200,
-                    items: vec![],
-                    is_selected: true,
-                    description: String::new(),
-                    root_path: PathBuf::new(),
-                },
-            ],
+    fn test_result(category: CategoryType, total_size: u64, is_selected: bool) -> ScanResult {
+        ScanResult {
+            category,
+            total_size,
+            items: vec![],
+            is_selected,
+            root_path: PathBuf::new(),
+            roots: Vec::new(),
+            timed_out: false,
+            duration: Duration::ZERO,
+            offloaded: 0,
+            warnings: Vec::new(),
+            note: None,
+            in_use: Vec::new(),
+        }
+    }
+
+    fn test_app(results: Vec<ScanResult>) -> App {
+        let total_categories = results.len();
+        let (rescan_tx, rescan_rx) = mpsc::channel();
+        App {
+            results,
             list_state: ListState::default(),
             state: AppState::Browsing,
             disks: Disks::new(),
             cleaning_rx: None,
             scan_rx: None,
             scan_progress: HashMap::new(),
-            total_categories: 2,
-        };
+            total_categories,
+            scan_options: Arc::new(ScanOptions::default()),
+            auto_select_target: None,
+            list_area: Rect::default(),
+            details_area: Rect::default(),
+            details_scroll: 0,
+            focus: Focus::Categories,
+            item_sort: ItemSort::default(),
+            theme: Theme::default(),
+            delete_mode: DeleteMethod::default(),
+            move_to: None,
+            view: View::Categories,
+            top_state: ListState::default(),
+            plan: None,
+            interrupted: Arc::default(),
+            tick: 0,
+            review_index: 0,
+            cleaning_batches: Vec::new(),
+            message: None,
+            denied: Vec::new(),
+            units: Units::default(),
+            downloads_min_age: Duration::from_secs(DOWNLOADS_MIN_AGE_DAYS * SECS_PER_DAY),
+            excluded: HashSet::new(),
+            installers_only: false,
+            find_duplicates: false,
+            duplicates: HashSet::new(),
+            duplicates_rx: None,
+            is_root: false,
+            allowlist: Allowlist::default(),
+            keep_latest_derived_data: true,
+            keep_recent: HashMap::new(),
+            rescan_tx,
+            rescan_rx,
+            pending_rescans: 0,
+            watch_interval: None,
+            last_refresh: Instant::now(),
+            last_disk_refresh: Instant::now(),
+            pinned: HashSet::new(),
+            expanded: HashMap::new(),
+            expanding: None,
+        }
+    }
+
+    #[test]
+    fn test_toggle_all() {
+        let mut app = test_app(vec![
+            test_result(CategoryType::XcodeJunk, 100, false),
+            test_result(CategoryType::SystemLogs, 200, true),
+        ]);
 
         // 1. Initially mixed (some true, some false). toggle_all should select all.
         app.toggle_all();
@@ -374,4 +1767,768 @@ mod tests {
         app.toggle_all();
         assert!(app.results.iter().all(|r| r.is_selected));
     }
+
+    fn click(column: u16, row: u16) -> MouseEvent {
+        MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column,
+            row,
+            modifiers: crossterm::event::KeyModifiers::NONE,
+        }
+    }
+
+    #[test]
+    fn test_mouse_click_selects_row() {
+        let mut app = test_app(vec![
+            test_result(CategoryType::XcodeJunk, 300, false),
+            test_result(CategoryType::SystemLogs, 200, false),
+            test_result(CategoryType::Trash, 100, false),
+        ]);
+        app.list_state.select(Some(0));
+        app.list_area = Rect::new(0, 3, 40, 10);
+
+        // Row 3 is the top border; the first category is drawn on row 4
+        app.handle_mouse(click(20, 6));
+        assert_eq!(app.list_state.selected(), Some(2));
+        assert!(!app.results[2].is_selected);
+
+        // Clicks on the border or below the last row don't change the selection
+        app.handle_mouse(click(20, 3));
+        app.handle_mouse(click(20, 8));
+        assert_eq!(app.list_state.selected(), Some(2));
+
+        // Clicking the checkbox toggles the category
+        app.handle_mouse(click(4, 5));
+        assert_eq!(app.list_state.selected(), Some(1));
+        assert!(app.results[1].is_selected);
+    }
+
+    fn progress(category: CategoryType, items_count: u64, items_total: u64) -> ScanProgress {
+        ScanProgress {
+            category,
+            items_count,
+            items_total,
+            bytes: 0,
+            status: "Scanning...".to_string(),
+            duration: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_scan_ratio() {
+        let mut app = test_app(vec![]);
+        app.total_categories = 4;
+        assert!(app.scan_ratio().abs() < f64::EPSILON);
+
+        // Nothing discovered yet counts as no progress
+        app.scan_progress
+            .insert(CategoryType::Trash, progress(CategoryType::Trash, 0, 0));
+        // Half of a category
+        app.scan_progress.insert(
+            CategoryType::Downloads,
+            progress(CategoryType::Downloads, 5, 10),
+        );
+        // A finished category counts fully, whatever its counters say
+        let mut done = progress(CategoryType::UserLogs, 0, 0);
+        done.status = "Done".to_string();
+        app.scan_progress.insert(CategoryType::UserLogs, done);
+
+        assert!((app.scan_ratio() - 1.5 / 4.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_auto_select_minimal_low_risk_set() {
+        const GIB: u64 = 1 << 30;
+        let mut app = test_app(vec![
+            test_result(CategoryType::Downloads, 100 * GIB, false),
+            test_result(CategoryType::Trash, 20 * GIB, false),
+            test_result(CategoryType::XcodeJunk, 8 * GIB, false),
+            test_result(CategoryType::UserCache, 5 * GIB, true),
+            test_result(CategoryType::NodeModules, 3 * GIB, false),
+        ]);
+        let selected = |app: &App| -> Vec<CategoryType> {
+            app.results
+                .iter()
+                .filter(|r| r.is_selected)
+                .map(|r| r.category)
+                .collect()
+        };
+
+        // The largest low-risk category alone covers the target
+        assert!(app.auto_select(4 * GIB));
+        assert_eq!(selected(&app), vec![CategoryType::XcodeJunk]);
+
+        // Low-risk categories are used up before medium-risk ones
+        assert!(app.auto_select(10 * GIB));
+        assert_eq!(
+            selected(&app),
+            vec![CategoryType::XcodeJunk, CategoryType::UserCache]
+        );
+        assert!(app.auto_select(20 * GIB));
+        assert_eq!(
+            selected(&app),
+            vec![
+                CategoryType::Trash,
+                CategoryType::XcodeJunk,
+                CategoryType::UserCache,
+                CategoryType::NodeModules
+            ]
+        );
+
+        // High-risk categories are never auto-selected
+        assert!(!app.auto_select(100 * GIB));
+        assert!(!app.results[0].is_selected);
+        assert_eq!(app.total_selected_size(), 36 * GIB);
+    }
+
+    fn item(path: &str, size: u64) -> ScannedItem {
+        ScannedItem {
+            path: PathBuf::from(path),
+            size,
+            modified: std::time::SystemTime::UNIX_EPOCH,
+        }
+    }
+
+    #[test]
+    fn top_items_are_sorted_across_categories() {
+        let mut cache = test_result(CategoryType::UserCache, 0, false);
+        cache.items = vec![item("/cache/a", 30), item("/cache/b", 5)];
+        let mut modules = test_result(CategoryType::NodeModules, 0, false);
+        modules.items = vec![item("/modules/a", 50), item("/modules/b", 10)];
+        let app = test_app(vec![cache, modules]);
+
+        let top: Vec<(CategoryType, u64)> = app
+            .top_items(3)
+            .into_iter()
+            .map(|(category, item)| (category, item.size))
+            .collect();
+        assert_eq!(
+            top,
+            vec![
+                (CategoryType::NodeModules, 50),
+                (CategoryType::UserCache, 30),
+                (CategoryType::NodeModules, 10),
+            ]
+        );
+    }
+
+    #[test]
+    fn expanding_an_item_lists_its_children_by_size() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let bundle = dir.path().join("com.apple.Safari");
+        std::fs::create_dir_all(bundle.join("WebKit"))?;
+        std::fs::write(bundle.join("WebKit/blob"), vec![0u8; 4096])?;
+        std::fs::write(bundle.join("Cache.db"), vec![0u8; 1024])?;
+        let mut cache = test_result(CategoryType::UserCache, 5120, false);
+        cache.items = vec![ScannedItem {
+            path: bundle.clone(),
+            size: 5120,
+            modified: SystemTime::UNIX_EPOCH,
+        }];
+        let mut app = test_app(vec![cache]);
+        app.list_state.select(Some(0));
+
+        app.toggle_expand();
+        let started = Instant::now();
+        while app.expanding.is_some() && started.elapsed() < Duration::from_secs(10) {
+            app.check_expand_status();
+            thread::sleep(Duration::from_millis(10));
+        }
+        let children: Vec<PathBuf> = app.expanded[&bundle]
+            .iter()
+            .map(|child| child.path.clone())
+            .collect();
+        assert_eq!(
+            children,
+            vec![bundle.join("WebKit"), bundle.join("Cache.db")]
+        );
+
+        app.toggle_expand();
+        assert!(app.expanded.is_empty());
+
+        // Results changing collapses what was expanded, as it may be stale
+        app.expanded.insert(bundle.clone(), Vec::new());
+        app.remove_cleaned(&HashSet::from([bundle]));
+        assert!(app.expanded.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn selection_is_clamped_when_results_shrink() {
+        let results = (0..5)
+            .map(|i| test_result(CategoryType::UserCache, i, false))
+            .collect();
+        let mut app = test_app(results);
+        app.list_state.select(Some(4));
+
+        app.results.truncate(2);
+        app.clamp_selection();
+        assert_eq!(app.list_state.selected(), Some(1));
+
+        app.results.clear();
+        app.clamp_selection();
+        assert_eq!(app.list_state.selected(), None);
+    }
+
+    #[test]
+    fn keep_recent_leaves_the_newest_items_unselected() {
+        let mut screenshots = test_result(CategoryType::ScreenCapture, 30, false);
+        screenshots.items = (0..30)
+            .map(|i| ScannedItem {
+                path: PathBuf::from(format!("/Desktop/Screenshot {i}.png")),
+                size: 1,
+                modified: SystemTime::UNIX_EPOCH + Duration::from_secs(i),
+            })
+            .collect();
+        let mut app = test_app(vec![screenshots]);
+        app.keep_recent.insert(CategoryType::ScreenCapture, 20);
+        app.list_state.select(Some(0));
+        app.toggle();
+
+        let batches = app.selected_batches();
+        let selectable: Vec<&Path> = batches[0].1.iter().map(|i| i.path.as_path()).collect();
+        assert_eq!(selectable.len(), 10);
+        assert!(selectable.contains(&Path::new("/Desktop/Screenshot 9.png")));
+        assert!(!selectable.contains(&Path::new("/Desktop/Screenshot 10.png")));
+    }
+
+    #[test]
+    fn denied_paths_are_only_offered_once() {
+        let mut app = test_app(vec![test_result(CategoryType::SystemLogs, 10, false)]);
+        app.denied = vec![PathBuf::from("/private/var/log/old.log")];
+        app.state = AppState::Done(String::new());
+        app.dismiss_done();
+        assert!(app.denied.is_empty());
+        assert!(matches!(app.state, AppState::Browsing));
+
+        let (tx, rx) = mpsc::channel();
+        app.cleaning_rx = Some(rx);
+        app.denied = vec![PathBuf::from("/private/var/log/old.log")];
+        tx.send(Err("Error during cleaning".to_string())).unwrap();
+        app.check_cleaning_status();
+        assert!(app.denied.is_empty());
+    }
+
+    #[test]
+    fn duplicates_are_found_in_the_background() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let size = usize::try_from(DUPLICATE_MIN_SIZE)?;
+        let copies = [dir.path().join("a.dmg"), dir.path().join("b.dmg")];
+        for copy in &copies {
+            std::fs::write(copy, vec![7u8; size])?;
+        }
+        let mut downloads = test_result(CategoryType::Downloads, 0, false);
+        downloads.items = copies
+            .iter()
+            .map(|path| ScannedItem {
+                path: path.clone(),
+                size: DUPLICATE_MIN_SIZE,
+                modified: SystemTime::UNIX_EPOCH,
+            })
+            .collect();
+        let mut app = test_app(vec![downloads]);
+
+        app.start_finding_duplicates();
+        let started = Instant::now();
+        while app.duplicates_rx.is_some() && started.elapsed() < Duration::from_secs(10) {
+            app.check_duplicates_status();
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(app.duplicates, copies.into_iter().collect());
+        Ok(())
+    }
+
+    #[test]
+    fn staging_a_top_item_plans_only_that_item() {
+        let mut cache = test_result(CategoryType::UserCache, 35, true);
+        cache.items = vec![item("/cache/a", 30), item("/cache/b", 5)];
+        let mut app = test_app(vec![cache]);
+
+        app.toggle_view();
+        app.next_top_item();
+        app.stage_top_item();
+
+        assert!(matches!(app.state, AppState::Confirming));
+        assert_eq!(app.planned_size(), 5);
+        assert_eq!(
+            app.planned_batches()[0].1[0].path,
+            PathBuf::from("/cache/b")
+        );
+
+        app.cancel_confirm();
+        assert!(app.plan.is_none());
+        assert_eq!(app.planned_size(), 35);
+    }
+
+    #[test]
+    fn staging_the_highlighted_category_ignores_selection() {
+        let mut logs = test_result(CategoryType::UserLogs, 10, true);
+        logs.items = vec![item("/logs/a", 10)];
+        let mut cache = test_result(CategoryType::UserCache, 35, false);
+        cache.items = vec![item("/cache/a", 30), item("/cache/b", 5)];
+        let mut app = test_app(vec![logs, cache]);
+
+        app.list_state.select(Some(1));
+        app.stage_highlighted_category();
+
+        assert!(matches!(app.state, AppState::Confirming));
+        let batches = app.planned_batches();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].0, CategoryType::UserCache);
+        assert_eq!(app.planned_size(), 35);
+        // Selections are left as they were
+        assert!(app.results[0].is_selected);
+        assert!(!app.results[1].is_selected);
+
+        app.cancel_confirm();
+        assert_eq!(app.planned_size(), 10);
+    }
+
+    #[test]
+    fn rescan_replaces_the_category_result() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(dir.path().join("left-behind"), [0u8; 7])?;
+        let mut cache = test_result(CategoryType::UserCache, 100, true);
+        cache.items = vec![item("/cache/gone", 100)];
+        let mut app = test_app(vec![cache, test_result(CategoryType::Trash, 5, false)]);
+        // Stopping the first scan with Esc doesn't stop later rescans
+        app.cancel_scan();
+
+        app.rescan_with(Box::new(scanner::PathScanner {
+            category: CategoryType::UserCache,
+            paths: vec![dir.path().to_path_buf()],
+            flatten_files: false,
+        }));
+        let fresh = app.rescan_rx.recv_timeout(Duration::from_secs(10))?;
+        app.rescan_tx.send(fresh)?;
+        app.check_rescan_status();
+
+        assert_eq!(app.results[0].total_size, 7);
+        assert_eq!(app.results[0].items[0].path, dir.path().join("left-behind"));
+        assert!(app.results[0].is_selected);
+        assert_eq!(app.results[1].total_size, 5);
+        Ok(())
+    }
+
+    #[test]
+    fn rescans_leave_new_recent_downloads_unselected() -> anyhow::Result<()> {
+        let mut downloads = test_result(CategoryType::Downloads, 100, true);
+        downloads.items = vec![item("/dl/old.dmg", 100)];
+        let mut app = test_app(vec![downloads]);
+
+        let mut fresh = test_result(CategoryType::Downloads, 150, false);
+        let mut recent = item("/dl/new.dmg", 50);
+        recent.modified = SystemTime::now();
+        fresh.items = vec![item("/dl/old.dmg", 100), recent];
+        app.rescan_tx.send(fresh)?;
+        app.check_rescan_status();
+
+        assert!(app.results[0].is_selected);
+        assert!(app.excluded.contains(Path::new("/dl/new.dmg")));
+        assert!(!app.excluded.contains(Path::new("/dl/old.dmg")));
+        Ok(())
+    }
+
+    #[test]
+    fn pinned_categories_sort_first() {
+        let mut app = test_app(vec![
+            test_result(CategoryType::UserCache, 300, false),
+            test_result(CategoryType::Trash, 5, false),
+            test_result(CategoryType::NodeModules, 200, false),
+            test_result(CategoryType::XcodeJunk, 10, false),
+        ]);
+        app.pinned = HashSet::from([CategoryType::Trash, CategoryType::XcodeJunk]);
+        app.apply_sort();
+
+        let order: Vec<CategoryType> = app.results.iter().map(|r| r.category).collect();
+        assert_eq!(
+            order,
+            vec![
+                CategoryType::XcodeJunk,
+                CategoryType::Trash,
+                CategoryType::UserCache,
+                CategoryType::NodeModules
+            ]
+        );
+    }
+
+    #[test]
+    fn watch_refreshes_when_idle_and_due() {
+        let mut app = test_app(vec![test_result(CategoryType::Trash, 5, false)]);
+        let start = app.last_refresh;
+        let later = start + Duration::from_secs(30);
+        assert!(!app.should_refresh(later));
+
+        app.watch_interval = Some(Duration::from_secs(30));
+        assert!(!app.should_refresh(start + Duration::from_secs(29)));
+        assert!(app.should_refresh(later));
+
+        app.state = AppState::Confirming;
+        assert!(!app.should_refresh(later));
+        app.state = AppState::Cleaning;
+        assert!(!app.should_refresh(later));
+
+        app.state = AppState::Browsing;
+        app.pending_rescans = 1;
+        assert!(!app.should_refresh(later));
+    }
+
+    #[test]
+    fn navigation_moves_in_the_focused_pane() {
+        let mut cache = test_result(CategoryType::UserCache, 30, false);
+        cache.items = vec![
+            item("/cache/a", 10),
+            item("/cache/b", 10),
+            item("/cache/c", 10),
+        ];
+        let mut app = test_app(vec![cache, test_result(CategoryType::Trash, 5, false)]);
+        app.list_state.select(Some(0));
+        assert_eq!(app.focus, Focus::Categories);
+
+        app.toggle_focus();
+        assert_eq!(app.focus, Focus::Details);
+        app.navigate_down();
+        app.navigate_down();
+        assert_eq!(app.details_scroll, 2);
+        assert_eq!(app.list_state.selected(), Some(0));
+        app.navigate_up();
+        assert_eq!(app.details_scroll, 1);
+
+        app.toggle_focus();
+        assert_eq!(app.focus, Focus::Categories);
+        app.navigate_down();
+        assert_eq!(app.list_state.selected(), Some(1));
+        assert_eq!(app.details_scroll, 0);
+    }
+
+    #[test]
+    fn unattended_selection_refuses_high_risk() {
+        let old = ScannedItem {
+            modified: SystemTime::UNIX_EPOCH,
+            ..item("/dl/old.dmg", 20)
+        };
+        let mut downloads = test_result(CategoryType::Downloads, 20, false);
+        downloads.items = vec![old];
+        let mut cache = test_result(CategoryType::UserCache, 10, false);
+        cache.items = vec![item("/cache/a", 10)];
+        let mut app = test_app(vec![downloads, cache]);
+
+        assert_eq!(
+            app.select_unattended(Risk::Medium),
+            vec![CategoryType::Downloads]
+        );
+        assert!(!app.results[0].is_selected);
+        assert!(app.results[1].is_selected);
+        assert_eq!(app.total_selected_size(), 10);
+
+        assert!(app.select_unattended(Risk::High).is_empty());
+        assert_eq!(app.total_selected_size(), 30);
+    }
+
+    #[test]
+    fn interrupt_waits_for_the_clean_to_stop() {
+        let mut app = test_app(vec![]);
+        assert!(!app.handle_interrupt());
+
+        app.interrupted.store(true, Ordering::Relaxed);
+        app.state = AppState::Cleaning;
+        assert!(!app.handle_interrupt());
+        // The stopped clean reports back and its Done screen stays up
+        let (tx, rx) = mpsc::channel();
+        app.cleaning_rx = Some(rx);
+        tx.send(Ok((String::new(), CleanReport::default())))
+            .unwrap();
+        app.check_cleaning_status();
+        assert!(matches!(app.state, AppState::Done(_)));
+        assert!(!app.handle_interrupt());
+        app.interrupted.store(true, Ordering::Relaxed);
+        assert!(app.handle_interrupt());
+
+        app.state = AppState::Scanning;
+        assert!(app.handle_interrupt());
+        assert!(app.scan_options.is_cancelled());
+    }
+
+    #[test]
+    fn progress_is_batched_per_interval() {
+        let start = Instant::now();
+        let interval = Duration::from_millis(100);
+        let throttle = ProgressThrottle::new(CategoryType::UserCache, interval, start);
+
+        assert!(throttle.record(ProgressEvent::Found(3), start).is_none());
+        for _ in 0..2 {
+            assert!(
+                throttle
+                    .record(ProgressEvent::Done(10), start + interval / 2)
+                    .is_none()
+            );
+        }
+        let progress = throttle
+            .record(ProgressEvent::Done(10), start + interval)
+            .expect("the window has passed");
+        assert_eq!(
+            (progress.items_count, progress.items_total, progress.bytes),
+            (3, 3, 30)
+        );
+        assert!(throttle.flush().is_none());
+
+        assert!(
+            throttle
+                .record(ProgressEvent::Found(1), start + interval)
+                .is_none()
+        );
+        assert_eq!(throttle.flush().map(|p| p.items_total), Some(1));
+    }
+
+    #[test]
+    fn reclaimable_and_selected_totals() {
+        let mut downloads = test_result(CategoryType::Downloads, 30, true);
+        downloads.items = vec![item("/dl/a", 20), item("/dl/b", 10)];
+        let mut cache = test_result(CategoryType::UserCache, 100, false);
+        cache.items = vec![item("/cache/a", 100)];
+        let mut app = test_app(vec![downloads, cache]);
+        app.excluded.insert(PathBuf::from("/dl/b"));
+
+        assert_eq!(app.total_size_all(), 130);
+        assert_eq!(app.total_selected_size(), 20);
+
+        app.results[1].is_selected = true;
+        assert_eq!(app.total_size_all(), 130);
+        assert_eq!(app.total_selected_size(), 120);
+    }
+
+    #[test]
+    fn quitting_with_a_selection_asks_first() {
+        let mut cache = test_result(CategoryType::UserCache, 10, false);
+        cache.items = vec![item("/cache/a", 10)];
+        let mut app = test_app(vec![cache]);
+
+        assert!(app.request_quit());
+        assert!(matches!(app.state, AppState::Browsing));
+
+        app.list_state.select(Some(0));
+        app.toggle();
+        assert!(!app.request_quit());
+        assert!(matches!(app.state, AppState::ConfirmQuit));
+    }
+
+    #[test]
+    fn disks_refresh_periodically() {
+        let mut app = test_app(vec![]);
+        let start = app.last_disk_refresh;
+        let due = start + Duration::from_secs(DISK_REFRESH_SECS);
+        assert!(!app.should_refresh_disks(start + Duration::from_secs(1)));
+        assert!(app.should_refresh_disks(due));
+
+        app.refresh_disks(due);
+        assert_eq!(app.last_disk_refresh, due);
+        assert!(!app.should_refresh_disks(due + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn disk_pressure_threshold() {
+        assert_eq!(DiskPressure::from_free_ratio(0.04), DiskPressure::Critical);
+        assert_eq!(
+            DiskPressure::from_free_ratio(0.0999),
+            DiskPressure::Critical
+        );
+        assert_eq!(DiskPressure::from_free_ratio(0.10), DiskPressure::Normal);
+        assert_eq!(DiskPressure::from_free_ratio(0.5), DiskPressure::Normal);
+    }
+
+    #[test]
+    fn reveal_args_per_path_type() {
+        assert_eq!(
+            reveal_args(Path::new("/Users/test/big.dmg"), false),
+            Some(vec![
+                OsString::from("-R"),
+                OsString::from("/Users/test/big.dmg")
+            ])
+        );
+        assert_eq!(
+            reveal_args(Path::new("/Users/test/Library/Caches"), true),
+            Some(vec![OsString::from("/Users/test/Library/Caches")])
+        );
+        assert_eq!(
+            reveal_args(Path::new("docker://abc123/<none>:<none>"), false),
+            None
+        );
+    }
+
+    #[test]
+    fn clipboard_text_per_path_type() {
+        assert_eq!(
+            clipboard_text(Path::new("/Users/test/Library/Caches/com.app")),
+            "/Users/test/Library/Caches/com.app"
+        );
+        assert_eq!(
+            clipboard_text(Path::new("docker://abc123/nginx:<none>")),
+            "abc123"
+        );
+        assert_eq!(
+            clipboard_text(Path::new("podman://def456/<none>:<none>")),
+            "def456"
+        );
+    }
+
+    #[test]
+    fn net_estimate_leaves_out_likely_skips() {
+        let mut logs = test_result(CategoryType::SystemLogs, 300, true);
+        logs.items = vec![
+            item("/Library/Logs/a.log", 100),
+            item("/Users/test/Library/Logs/b.log", 200),
+        ];
+        let mut cache = test_result(CategoryType::UserCache, 1536, true);
+        cache.items = vec![
+            item("/Users/test/Library/Caches/busy", 512),
+            item("/Users/test/Library/Caches/keep", 256),
+            item("/Users/test/Library/Caches/old", 768),
+        ];
+        cache.in_use = vec![PathBuf::from("/Users/test/Library/Caches/busy")];
+        let mut app = test_app(vec![logs, cache]);
+        // Selected by hand, so it is planned despite being in use
+        app.excluded.clear();
+        app.allowlist = Allowlist::new(vec!["/Users/test/Library/Caches/keep".to_string()]);
+
+        // Open files are removed all the same, so only the others count
+        assert_eq!(app.planned_size(), 1836);
+        assert_eq!(app.net_estimate(), (1480, 2));
+
+        app.is_root = true;
+        assert_eq!(app.net_estimate(), (1580, 1));
+        let kept: Vec<PathBuf> = app
+            .without_allowlisted(app.planned_batches())
+            .into_iter()
+            .flat_map(|(_, items)| items)
+            .map(|item| item.path)
+            .collect();
+        assert!(!kept.contains(&PathBuf::from("/Users/test/Library/Caches/keep")));
+        assert_eq!(kept.len(), 4);
+
+        app.allowlist = Allowlist::default();
+        assert_eq!(app.net_estimate(), (1836, 0));
+    }
+
+    #[test]
+    fn warns_about_system_paths_without_root() {
+        let mut logs = test_result(CategoryType::SystemLogs, 300, true);
+        logs.items = vec![
+            item("/Library/Logs/a.log", 100),
+            item("/private/var/log/b.log", 200),
+        ];
+        let mut cache = test_result(CategoryType::UserCache, 512, true);
+        cache.items = vec![item("/Users/test/Library/Caches/a", 512)];
+        let mut app = test_app(vec![logs, cache]);
+
+        let warning = app.privileged_warning().unwrap();
+        assert!(warning.starts_with("2 items"));
+
+        app.is_root = true;
+        assert_eq!(app.privileged_warning(), None);
+    }
+
+    #[test]
+    fn plan_summary_lists_selected_categories() {
+        let mut downloads = test_result(CategoryType::Downloads, 2048, true);
+        downloads.items = vec![item("/dl/a", 2048)];
+        let mut trash = test_result(CategoryType::Trash, 1024, true);
+        trash.items = vec![item("/trash/a", 1024)];
+        let mut cache = test_result(CategoryType::UserCache, 512, false);
+        cache.items = vec![item("/cache/a", 512)];
+        let app = test_app(vec![downloads, trash, cache]);
+
+        let summary = app.plan_summary();
+        let lines: Vec<Vec<&str>> = summary
+            .lines()
+            .map(|line| line.split_whitespace().collect())
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                vec!["Downloads", "2", "KiB"],
+                vec!["Trash", "1", "KiB"],
+                vec!["Total", "3", "KiB"],
+            ]
+        );
+    }
+
+    #[test]
+    fn planned_total_counts_items() {
+        let mut downloads = test_result(CategoryType::Downloads, 3072, true);
+        downloads.items = vec![item("/dl/a", 2048), item("/dl/b", 1024)];
+        let mut trash = test_result(CategoryType::Trash, 1024, true);
+        trash.items = vec![item("/trash/a", 1024)];
+        let mut cache = test_result(CategoryType::UserCache, 512, false);
+        cache.items = vec![item("/cache/a", 512)];
+        let mut app = test_app(vec![downloads, trash, cache]);
+
+        assert_eq!(app.planned_count(), 3);
+        assert_eq!(app.planned_total(), "4 KiB across 3 items");
+
+        app.results[1].is_selected = false;
+        app.excluded.insert(PathBuf::from("/dl/b"));
+        assert_eq!(app.planned_total(), "2 KiB across 1 item");
+    }
+
+    #[test]
+    fn removing_a_category_from_the_plan() {
+        let mut downloads = test_result(CategoryType::Downloads, 2048, true);
+        downloads.items = vec![item("/dl/a", 2048)];
+        let mut trash = test_result(CategoryType::Trash, 1024, true);
+        trash.items = vec![item("/trash/a", 1024)];
+        let mut app = test_app(vec![downloads, trash]);
+        app.state = AppState::Confirming;
+        assert_eq!(app.planned_size(), 3072);
+
+        app.move_review(1);
+        app.move_review(1);
+        assert_eq!(app.review_index, 1);
+        app.remove_from_plan();
+        assert_eq!(app.planned_size(), 2048);
+        assert!(!app.is_trash_planned());
+        assert_eq!(app.review_index, 0);
+        // The selections themselves are left alone
+        assert!(app.results[1].is_selected);
+
+        app.remove_from_plan();
+        assert!(matches!(app.state, AppState::Browsing));
+        assert_eq!(app.planned_size(), 3072);
+    }
+
+    #[test]
+    fn toggling_downloads_selects_only_old_items() {
+        let mut downloads = test_result(CategoryType::Downloads, 110, false);
+        downloads.items = vec![
+            item("/dl/old.dmg", 100),
+            ScannedItem {
+                path: PathBuf::from("/dl/new.pdf"),
+                size: 10,
+                modified: SystemTime::now(),
+            },
+        ];
+        let mut app = test_app(vec![downloads]);
+        app.list_state.select(Some(0));
+
+        app.toggle();
+        assert_eq!(app.total_selected_size(), 100);
+        let batches = app.selected_batches();
+        assert_eq!(batches[0].1.len(), 1);
+        assert_eq!(batches[0].1[0].path, PathBuf::from("/dl/old.dmg"));
+
+        app.toggle();
+        assert!(app.excluded.is_empty());
+        assert_eq!(app.total_selected_size(), 0);
+    }
+
+    #[test]
+    fn caches_in_use_stay_unselected() {
+        let mut cache = test_result(CategoryType::UserCache, 30, false);
+        cache.items = vec![item("/cache/busy", 20), item("/cache/idle", 10)];
+        cache.in_use = vec![PathBuf::from("/cache/busy")];
+        let mut app = test_app(vec![cache]);
+        app.list_state.select(Some(0));
+
+        app.toggle();
+        assert_eq!(app.total_selected_size(), 10);
+    }
 }
@@ -1,29 +1,117 @@
-use crate::model::CategoryType;
-use crate::ui::app::{App, AppState};
-use humansize::{BINARY, format_size};
+use crate::cleaner::DeleteMethod;
+use crate::constants::{SECS_PER_DAY, SIZE_BAR_WIDTH, SPINNER_FRAMES, VERSION};
+use crate::model::{CategoryType, ItemSort, ScanResult, ScannedItem};
+use crate::scanner::container::ContainerEngine;
+use crate::ui::app::{App, AppState, DiskPressure, Focus, TOP_ITEMS_LIMIT, View};
+use crate::units;
 use ratatui::{
     prelude::*,
     widgets::{
         Block, BorderType, Borders, Cell, Clear, Gauge, List, ListItem, Paragraph, Row, Table, Wrap,
     },
 };
+use std::fmt::Write as _;
+use std::time::{Duration, SystemTime};
+
+/// The left part of a category row: checkbox, name and pin, padded so sizes
+/// line up, e.g. `[x] Trash             ★`.
+fn category_label(result: &ScanResult, pinned: bool) -> String {
+    let checkbox = if result.is_selected { "[x]" } else { "[ ]" };
+    let pin = if pinned { "★" } else { " " };
+    format!("{checkbox} {:<18}{pin}", result.category.name())
+}
+
+/// The item count at the end of a category row, e.g. `4 items`. Last in the
+/// row, so narrow panes cut it off before the size.
+fn item_count_label(count: usize) -> String {
+    format!(
+        "{} {}",
+        units::format_count(count),
+        if count == 1 { "item" } else { "items" }
+    )
+}
+
+/// The spinner frame to show at event loop tick `tick`.
+fn spinner_frame(tick: usize) -> &'static str {
+    SPINNER_FRAMES[tick % SPINNER_FRAMES.len()]
+}
 
-const COLOR_PRIMARY: Color = Color::Cyan;
-const COLOR_SECONDARY: Color = Color::Blue;
-const COLOR_ACCENT: Color = Color::Magenta;
-const COLOR_BORDER: Color = Color::DarkGray;
+/// Names an item in the details table. Container images have no file name,
+/// so they show their repository, tag and age instead of the virtual path.
+/// Recursively found directories are all called `node_modules` or `target`,
+/// so they are prefixed with their project, e.g. `myapp/node_modules`.
+fn item_name(category: CategoryType, item: &ScannedItem, now: SystemTime) -> String {
+    if let Some(image) = ContainerEngine::image_name(&item.path) {
+        let age = now.duration_since(item.modified).unwrap_or_default();
+        return format!("{image} — {}", format_age(age));
+    }
+    let name = item.path.file_name().unwrap_or_default().to_string_lossy();
+    let project = item
+        .path
+        .parent()
+        .and_then(|parent| parent.file_name())
+        .map(|project| project.to_string_lossy());
+    match (category, project) {
+        (CategoryType::NodeModules | CategoryType::BuildArtifacts, Some(project)) => {
+            format!("{project}/{name}")
+        }
+        _ => name.into_owned(),
+    }
+}
+
+/// Formats an age coarsely, e.g. `3 weeks old`.
+fn format_age(age: Duration) -> String {
+    const UNITS: [(&str, u64); 6] = [
+        ("year", 365 * SECS_PER_DAY),
+        ("month", 30 * SECS_PER_DAY),
+        ("week", 7 * SECS_PER_DAY),
+        ("day", SECS_PER_DAY),
+        ("hour", 60 * 60),
+        ("minute", 60),
+    ];
+    let secs = age.as_secs();
+    UNITS.iter().find(|(_, unit)| secs >= *unit).map_or_else(
+        || "just created".to_string(),
+        |(name, unit)| {
+            let count = secs / unit;
+            let plural = if count == 1 { "" } else { "s" };
+            format!("{count} {name}{plural} old")
+        },
+    )
+}
+
+/// Shortens `text` to at most `max_chars` characters by replacing its start
+/// with `...`. Counts characters rather than bytes, so paths with multibyte
+/// names (`スクリーンショット`) are never cut inside a character.
+fn truncate_start(text: &str, max_chars: usize) -> String {
+    let len = text.chars().count();
+    if len <= max_chars {
+        return text.to_string();
+    }
+    let keep = max_chars.saturating_sub(3);
+    let tail: String = text.chars().skip(len - keep).collect();
+    format!("...{tail}")
+}
+
+/// Draws `ratio` (0.0 to 1.0) as a bar of `width` cells, e.g. `█████░░░░░` for half.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_precision_loss
+)]
+fn size_bar(ratio: f64, width: usize) -> String {
+    let filled = ((ratio.clamp(0.0, 1.0) * width as f64).round() as usize).min(width);
+    format!("{}{}", "█".repeat(filled), "░".repeat(width - filled))
+}
+
+fn header_title() -> String {
+    format!("sukkiri v{VERSION}")
+}
 
 #[allow(clippy::cast_precision_loss)]
 pub fn render_header(f: &mut Frame, app: &App, area: Rect) {
-    let disk_info = app
-        .disks
-        .list()
-        .iter()
-        .find(|d| d.mount_point() == std::path::Path::new("/"));
-
-    let (percent, label) = if let Some(disk) = disk_info {
-        let total = disk.total_space();
-        let available = disk.available_space();
+    let critical = app.disk_pressure() == DiskPressure::Critical;
+    let (percent, label) = if let Some((total, available)) = app.root_disk_space() {
         let used = total.saturating_sub(available);
         let ratio = if total > 0 {
             used as f64 / total as f64
@@ -34,12 +122,21 @@ pub fn render_header(f: &mut Frame, app: &App, area: Rect) {
         // Ratatui Gauge uses .ratio(0.0..1.0) or .percent(0..100). Let's use ratio.
         (
             ratio.clamp(0.0, 1.0),
-            format!(
-                "Disk: {} / {} ({:.1}% Used)",
-                format_size(used, BINARY),
-                format_size(total, BINARY),
-                ratio * 100.0
-            ),
+            if critical {
+                format!(
+                    "Disk critically full — {:.0}% free ({} / {} Used)",
+                    (1.0 - ratio) * 100.0,
+                    app.fmt_size(used),
+                    app.fmt_size(total)
+                )
+            } else {
+                format!(
+                    "Disk: {} / {} ({:.1}% Used)",
+                    app.fmt_size(used),
+                    app.fmt_size(total),
+                    ratio * 100.0
+                )
+            },
         )
     } else {
         (0.0, "Disk: N/A".to_string())
@@ -48,12 +145,20 @@ pub fn render_header(f: &mut Frame, app: &App, area: Rect) {
     let gauge = Gauge::default()
         .block(
             Block::default()
-                .title("sukkiri v0.1.0")
+                .title(header_title())
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(COLOR_BORDER)),
+                .border_style(Style::default().fg(app.theme.border)),
+        )
+        .gauge_style(
+            Style::default()
+                .fg(if critical {
+                    app.theme.warning
+                } else {
+                    app.theme.secondary
+                })
+                .bg(Color::Black),
         )
-        .gauge_style(Style::default().fg(COLOR_SECONDARY).bg(Color::Black))
         .ratio(percent)
         .label(label)
         .use_unicode(true);
@@ -61,46 +166,69 @@ pub fn render_header(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(gauge, area);
 }
 
+#[allow(clippy::cast_precision_loss)]
 pub fn render_categories_list(f: &mut Frame, app: &mut App, area: Rect) {
+    // With the disk nearly full, point out where most space can be won
+    let biggest_wins = app.biggest_wins();
+    let total_all_size = app.total_size_all();
     let items: Vec<ListItem> = app
         .results
         .iter()
         .map(|r| {
-            let checkbox = if r.is_selected { "[x]" } else { "[ ]" };
-            let size_str = format_size(r.total_size, BINARY);
+            let size_str = app.fmt_size(r.total_size);
+            let size_style = if biggest_wins.contains(&r.category) {
+                Style::default()
+                    .fg(app.theme.warning)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(app.theme.primary)
+            };
             let content = Line::from(vec![
                 Span::styled(
-                    format!("{} {:<18}", checkbox, r.category.name()),
+                    category_label(r, app.pinned.contains(&r.category)),
                     Style::default(),
                 ),
+                Span::styled(format!("{size_str:>10}"), size_style),
                 Span::styled(
-                    format!("{size_str:>10}"),
-                    Style::default().fg(COLOR_PRIMARY),
+                    format!(
+                        " {}",
+                        size_bar(
+                            r.total_size as f64 / total_all_size.max(1) as f64,
+                            SIZE_BAR_WIDTH
+                        )
+                    ),
+                    Style::default().fg(app.theme.secondary),
+                ),
+                Span::styled(
+                    format!("  {}", item_count_label(r.items.len())),
+                    Style::default().fg(app.theme.muted),
                 ),
             ]);
             ListItem::new(content)
         })
         .collect();
 
-    let total_all_size: u64 = app.results.iter().map(|r| r.total_size).sum();
-
     // We want to render the list, and at the bottom the total size.
     // Ratatui List doesn't have a "footer" for the block easily unless we use Block title_bottom.
     // Or we can manually render the Total line below the list if we split the area, but Block title is easier.
 
-    let total_text = format!(" Total: {} ", format_size(total_all_size, BINARY));
+    let total_text = format!(
+        " Reclaimable: {} | Selected: {} ",
+        app.fmt_size(total_all_size),
+        app.fmt_size(app.total_selected_size())
+    );
 
     let list = List::new(items)
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(COLOR_BORDER))
+                .border_style(pane_border(app, Focus::Categories))
                 .title("Categories")
                 .title_bottom(
                     Line::from(total_text).alignment(Alignment::Right).style(
                         Style::default()
-                            .fg(COLOR_PRIMARY)
+                            .fg(app.theme.primary)
                             .add_modifier(Modifier::BOLD),
                     ),
                 ),
@@ -108,7 +236,7 @@ pub fn render_categories_list(f: &mut Frame, app: &mut App, area: Rect) {
         .highlight_style(
             Style::default()
                 .add_modifier(Modifier::BOLD)
-                .fg(COLOR_ACCENT),
+                .fg(app.theme.accent),
         )
         .highlight_symbol("> ");
     f.render_stateful_widget(list, area, &mut app.list_state);
@@ -120,39 +248,80 @@ pub fn render_details_text(f: &mut Frame, app: &App, area: Rect) {
     if selected_index < app.results.len() {
         let selected_result = &app.results[selected_index];
 
-        let header_text = format!("Details: {}", selected_result.category.name());
+        let mut header_text = format!(
+            "Details: {} (scanned in {:.1}s",
+            selected_result.category.name(),
+            selected_result.duration.as_secs_f64()
+        );
+        match selected_result.roots.len() {
+            0 | 1 => header_text.push(')'),
+            n => {
+                let _ = write!(header_text, " from {n} locations)");
+            }
+        }
+        if selected_result.timed_out {
+            header_text.push_str(" (partial, timed out)");
+        }
+        if selected_result.offloaded > 0 {
+            header_text.push_str(" (some iCloud-offloaded files skipped)");
+        }
+        if let Some(note) = &selected_result.note {
+            let _ = write!(header_text, " ({note})");
+        }
+        for warning in &selected_result.warnings {
+            let _ = write!(header_text, " ⚠ {warning}");
+        }
+        if app.installers_only {
+            header_text.push_str(" (installers only)");
+        }
+        if app.item_sort != ItemSort::Size {
+            let _ = write!(header_text, " (by {})", app.item_sort.label());
+        }
 
         // Use a Table for large items
         let header_cells = ["Name", "Size", "Path"].iter().map(|h| {
             Cell::from(*h).style(
                 Style::default()
-                    .fg(COLOR_PRIMARY)
+                    .fg(app.theme.primary)
                     .add_modifier(Modifier::BOLD),
             )
         });
         let header = Row::new(header_cells).height(1).bottom_margin(1);
-
-        let rows = selected_result.items.iter().take(20).map(|item| {
-            let name = item.path.file_name().unwrap_or_default().to_string_lossy();
-            // Truncate path for display
-            let path_display = item.path.display().to_string();
-            // Simple truncation if too long
-            let path_short = if path_display.len() > 30 {
-                format!(
-                    "...{}",
-                    &path_display[path_display.len().saturating_sub(27)..]
-                )
-            } else {
-                path_display
-            };
-
-            let cells = vec![
-                Cell::from(name),
-                Cell::from(format_size(item.size, BINARY)),
-                Cell::from(path_short).style(Style::default().fg(Color::DarkGray)),
-            ];
-            Row::new(cells).height(1)
-        });
+        let kept = app.kept_items(selected_result);
+        let now = SystemTime::now();
+
+        let rows = app
+            .detail_items(selected_result)
+            .into_iter()
+            .skip(app.details_scroll)
+            .take(20)
+            .flat_map(|item| {
+                let name = format!(
+                    "{} {}",
+                    item.kind().tag(),
+                    item_name(selected_result.category, item, now)
+                );
+                // Truncate path for display
+                let path_display = match ContainerEngine::parse_path(&item.path) {
+                    Some((engine, id)) => format!("{} image {id}", engine.binary()),
+                    None => item.path.display().to_string(),
+                };
+                let path_short = truncate_start(&path_display, 30);
+
+                let cells = vec![
+                    Cell::from(name),
+                    Cell::from(app.fmt_size(item.size)),
+                    Cell::from(path_short).style(Style::default().fg(app.theme.muted)),
+                ];
+                let row = Row::new(cells).height(1);
+                // Recent downloads and current build data may still be needed
+                let row = if kept.contains(&item.path) {
+                    row.style(Style::default().fg(app.theme.warning))
+                } else {
+                    row
+                };
+                std::iter::once(row).chain(expansion_rows(app, item))
+            });
 
         let table = Table::new(
             rows,
@@ -167,7 +336,7 @@ pub fn render_details_text(f: &mut Frame, app: &App, area: Rect) {
             Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(COLOR_BORDER))
+                .border_style(pane_border(app, Focus::Details))
                 .title(header_text),
         )
         .column_spacing(1);
@@ -178,50 +347,233 @@ pub fn render_details_text(f: &mut Frame, app: &App, area: Rect) {
             Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(COLOR_BORDER))
+                .border_style(pane_border(app, Focus::Details))
                 .title("Details"),
             area,
         );
     }
 }
 
+/// The rows below an expanded item: its children, or a note while they are listed.
+fn expansion_rows(app: &App, item: &ScannedItem) -> Vec<Row<'static>> {
+    let mut rows: Vec<Row<'static>> = app
+        .expanded
+        .get(&item.path)
+        .map_or(&[][..], Vec::as_slice)
+        .iter()
+        .map(|child| expanded_row(app, child))
+        .collect();
+    if app
+        .expanding
+        .as_ref()
+        .is_some_and(|expansion| expansion.path == item.path)
+    {
+        rows.push(
+            Row::new(vec![Cell::from("  └ Listing... [e] Stop")])
+                .style(Style::default().fg(app.theme.muted)),
+        );
+    }
+    rows
+}
+
+/// A child of an expanded item, indented below its parent.
+fn expanded_row<'a>(app: &App, child: &ScannedItem) -> Row<'a> {
+    let name = child.path.file_name().unwrap_or_default().to_string_lossy();
+    Row::new(vec![
+        Cell::from(format!("  └ {} {name}", child.kind().tag())),
+        Cell::from(app.fmt_size(child.size)),
+        Cell::from(truncate_start(&child.path.display().to_string(), 30))
+            .style(Style::default().fg(app.theme.muted)),
+    ])
+    .height(1)
+}
+
+/// Border of a categories-view pane, highlighted while it has the focus.
+fn pane_border(app: &App, pane: Focus) -> Style {
+    if app.focus == pane {
+        Style::default().fg(app.theme.accent)
+    } else {
+        Style::default().fg(app.theme.border)
+    }
+}
+
 pub fn render_details(f: &mut Frame, app: &App, area: Rect) {
     // Layout simplified: No chart, just details text in full area
     render_details_text(f, app, area);
 }
 
+/// Lists the largest items across all categories.
+pub fn render_top_items(f: &mut Frame, app: &mut App, area: Rect) {
+    let rows: Vec<ListItem> = app
+        .top_items(TOP_ITEMS_LIMIT)
+        .into_iter()
+        .map(|(category, item)| {
+            let mut spans = vec![
+                Span::styled(
+                    format!("{:>10} ", app.fmt_size(item.size)),
+                    Style::default().fg(app.theme.primary),
+                ),
+                Span::raw(format!("{:<22}", category.name())),
+                Span::styled(
+                    item.path.display().to_string(),
+                    Style::default().fg(app.theme.muted),
+                ),
+            ];
+            if app.duplicates.contains(&item.path) {
+                spans.push(Span::styled(
+                    " (duplicate)",
+                    Style::default().fg(app.theme.warning),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let list = List::new(rows)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(app.theme.border))
+                .title(format!("Top {TOP_ITEMS_LIMIT} Items")),
+        )
+        .highlight_style(
+            Style::default()
+                .add_modifier(Modifier::BOLD)
+                .fg(app.theme.accent),
+        )
+        .highlight_symbol("> ");
+    f.render_stateful_widget(list, area, &mut app.top_state);
+}
+
+pub fn render_empty_state(f: &mut Frame, app: &App, area: Rect) {
+    let message = Paragraph::new(vec![
+        Line::default(),
+        Line::styled(
+            crate::report::NOTHING_TO_CLEAN,
+            Style::default()
+                .fg(app.theme.success)
+                .add_modifier(Modifier::BOLD),
+        ),
+    ])
+    .alignment(Alignment::Center)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(app.theme.border)),
+    );
+    f.render_widget(message, area);
+}
+
 pub fn render_footer(f: &mut Frame, app: &App, area: Rect) {
-    let total_selected = app.total_selected_size();
+    let total_selected = app.planned_size();
     let footer_text = match app.state {
-        AppState::Browsing => format!(
-            "Total Selected: {} | [Space] Toggle [a] All [Enter] Clean [q] Quit",
-            format_size(total_selected, BINARY)
+        AppState::Browsing if app.message.is_some() => app.message.clone().unwrap_or_default(),
+        AppState::Browsing if app.view == View::TopItems => format!(
+            "Mode: {} | [Enter] Clean item [t] Categories [q] Quit",
+            app.mode_label()
         ),
-        AppState::Confirming => format!(
-            "CONFIRM CLEAN? Selected: {} | [y/Enter] Confirm [n/Esc] Cancel",
-            format_size(total_selected, BINARY)
+        AppState::Browsing => format!(
+            "Total Selected: {} | Mode: {} | [Tab] Pane [Space] Toggle [a] All [x] Clean this [p] Pin [t] Top [d] Mode [Enter] Clean [q] Quit",
+            app.fmt_size(total_selected),
+            app.mode_label()
         ),
+        AppState::Confirming => {
+            let action = match (app.delete_mode, app.is_trash_planned()) {
+                _ if app.move_to.is_some() => "CONFIRM MOVE of",
+                (DeleteMethod::Permanent, _) => "CONFIRM PERMANENT DELETE (cannot be undone) of",
+                (DeleteMethod::Trash, true) => "CONFIRM MOVE TO TRASH + Empty Trash (permanent) of",
+                (DeleteMethod::Trash, false) => "CONFIRM MOVE TO TRASH of",
+            };
+            format!(
+                "{action} {}? | [y/Enter] Confirm [n/Esc] Cancel",
+                app.planned_total()
+            )
+        }
         AppState::Cleaning => "Cleaning... (This may take a while)".to_string(),
-        AppState::Scanning => "Scanning... (Please wait)".to_string(),
-        AppState::Done(_) => "Done! [Press key to continue]".to_string(),
+        AppState::ConfirmQuit => format!(
+            "Quit without cleaning {} selected? [y] Quit [n/Esc] Back",
+            app.fmt_size(total_selected)
+        ),
+        AppState::Scanning if app.scan_options.is_cancelled() => "Stopping...".to_string(),
+        AppState::Scanning => "Scanning... [Esc] Stop and show results [q] Quit".to_string(),
+        AppState::Done(_) if !app.denied.is_empty() => {
+            "Done! [r] Retry as administrator [u] Undo [Enter] Continue".to_string()
+        }
+        AppState::Done(_) => "Done! [u] Undo [Enter] Continue".to_string(),
     };
 
     let footer = Paragraph::new(footer_text).block(
         Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
-            .border_style(Style::default().fg(COLOR_BORDER)),
+            .border_style(Style::default().fg(app.theme.border)),
     );
     f.render_widget(footer, area);
 }
 
 pub fn render_popup(f: &mut Frame, app: &App) {
+    if let AppState::Confirming = app.state {
+        let block = Block::default()
+            .title("Clean these categories?")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(app.theme.border));
+        let area = centered_rect(60, 40, f.area());
+        f.render_widget(Clear, area);
+        let summary = app.plan_summary();
+        let mut lines: Vec<Line> = summary
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                if i == app.review_index {
+                    Line::styled(line, Style::default().add_modifier(Modifier::REVERSED))
+                } else {
+                    Line::from(line)
+                }
+            })
+            .collect();
+        lines.push(Line::styled(
+            "[↑/↓] Move [Space] Remove from this clean",
+            Style::default().fg(app.theme.muted),
+        ));
+        lines.push(Line::default());
+        let plan = app.clean_plan().describe(app.units);
+        lines.extend(plan.lines().map(|line| Line::from(line.to_string())));
+        let (net, skipped) = app.net_estimate();
+        if skipped > 0 {
+            lines.push(Line::default());
+            lines.push(Line::styled(
+                format!(
+                    "⚠ Likely to free {} of {}: {} {} will probably be skipped.",
+                    app.fmt_size(net),
+                    app.fmt_size(app.planned_size()),
+                    units::format_count(skipped),
+                    if skipped == 1 { "item" } else { "items" }
+                ),
+                Style::default().fg(app.theme.warning),
+            ));
+        }
+        if let Some(warning) = app.privileged_warning() {
+            lines.push(Line::default());
+            lines.push(Line::styled(
+                format!("⚠ {warning}"),
+                Style::default().fg(app.theme.warning),
+            ));
+        }
+        f.render_widget(
+            Paragraph::new(lines).block(block).wrap(Wrap { trim: true }),
+            area,
+        );
+    }
+
     if let AppState::Done(ref msg) = app.state {
         let block = Block::default()
             .title("Clean Completed")
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
-            .border_style(Style::default().fg(COLOR_BORDER));
+            .border_style(Style::default().fg(app.theme.border));
         let area = centered_rect(60, 20, f.area());
         f.render_widget(Clear, area);
         f.render_widget(
@@ -244,18 +596,13 @@ pub fn render_scanning(f: &mut Frame, app: &App, area: Rect) {
         .split(area);
 
     // 1. Overall Gauge
-    let completed_count = app.results.len() as f64;
-    let total_count = app.total_categories as f64;
-    let ratio = if total_count > 0.0 {
-        completed_count / total_count
-    } else {
-        0.0
-    };
+    let ratio = app.scan_ratio();
 
     let label = format!(
-        "Scanning Categories: {} / {}",
+        "Scanning Categories: {} / {} ({:.0}%)",
         app.results.len(),
-        app.total_categories
+        app.total_categories,
+        ratio * 100.0
     );
 
     let gauge = Gauge::default()
@@ -264,9 +611,9 @@ pub fn render_scanning(f: &mut Frame, app: &App, area: Rect) {
                 .title("Scan Progress")
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(COLOR_BORDER)),
+                .border_style(Style::default().fg(app.theme.border)),
         )
-        .gauge_style(Style::default().fg(COLOR_PRIMARY).bg(Color::Black))
+        .gauge_style(Style::default().fg(app.theme.primary).bg(Color::Black))
         .ratio(ratio)
         .label(label)
         .use_unicode(true);
@@ -279,26 +626,40 @@ pub fn render_scanning(f: &mut Frame, app: &App, area: Rect) {
     // Show specific order if possible, or just iterate map
     // We want to show all categories and their status
 
-    // We can't iterate HashMap in consistent order unless we sort.
-    // Let's use the hardcoded order from main/start_scan if we can, or just sort keys.
-    let mut categories: Vec<CategoryType> = app.scan_progress.keys().copied().collect();
-    // Sort by name for now, or arbitrary stability
-    categories.sort_by_key(|c| c.name());
-
-    for cat in categories {
-        if let Some(prog) = app.scan_progress.get(&cat) {
-            let spinner = if prog.status == "Done" { "✔" } else { "⠋" };
-            let style = if prog.status == "Done" {
-                Style::default().fg(Color::Green)
+    // Follow the canonical category order rather than the HashMap's
+    for cat in CategoryType::all() {
+        if let Some(prog) = app.scan_progress.get(cat) {
+            let spinner = if prog.is_done() {
+                "✔"
+            } else {
+                spinner_frame(app.tick)
+            };
+            let style = if prog.is_done() {
+                Style::default().fg(app.theme.success)
             } else {
-                Style::default().fg(Color::Yellow)
+                Style::default().fg(app.theme.warning)
             };
 
+            let status = if prog.is_done() {
+                // e.g. why Docker images came back empty
+                let note = app
+                    .results
+                    .iter()
+                    .find(|r| r.category == *cat)
+                    .and_then(|r| r.note.as_ref())
+                    .map(|note| format!(" ({note})"))
+                    .unwrap_or_default();
+                format!("Done in {:.1}s{note}", prog.duration.as_secs_f64())
+            } else {
+                prog.status.clone()
+            };
             let content = Line::from(vec![
                 Span::styled(format!("{} {:<20}", spinner, prog.category.name()), style),
                 Span::raw(format!(
-                    "Items: {:<5} Status: {}",
-                    prog.items_count, prog.status
+                    "Items: {:>11} {:>4.0}% {:>10} Status: {status}",
+                    format!("{}/{}", prog.items_count, prog.items_total),
+                    prog.ratio() * 100.0,
+                    app.fmt_size(prog.bytes),
                 )),
             ]);
             items.push(ListItem::new(content));
@@ -334,3 +695,119 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         ])
         .split(popup_layout[1])[1]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn container_images_show_name_and_age() {
+        let now = SystemTime::now();
+        let image = ScannedItem {
+            path: "docker://abc123/nginx:<none>".into(),
+            size: 420 << 20,
+            modified: now - Duration::from_secs(3 * 7 * SECS_PER_DAY),
+        };
+        assert_eq!(
+            item_name(CategoryType::DockerImages, &image, now),
+            "nginx:<none> — 3 weeks old"
+        );
+
+        let file = ScannedItem {
+            path: "/Users/test/Downloads/Tool.dmg".into(),
+            size: 1,
+            modified: now,
+        };
+        assert_eq!(item_name(CategoryType::Downloads, &file, now), "Tool.dmg");
+    }
+
+    #[test]
+    fn recursive_items_are_labeled_with_their_project() {
+        let now = SystemTime::now();
+        let modules = ScannedItem {
+            path: "/Users/test/dev/myapp/node_modules".into(),
+            size: 1,
+            modified: now,
+        };
+        assert_eq!(
+            item_name(CategoryType::NodeModules, &modules, now),
+            "myapp/node_modules"
+        );
+        let target = ScannedItem {
+            path: "/Users/test/dev/cli/target".into(),
+            ..modules
+        };
+        assert_eq!(
+            item_name(CategoryType::BuildArtifacts, &target, now),
+            "cli/target"
+        );
+    }
+
+    #[test]
+    fn truncate_start_respects_char_boundaries() {
+        assert_eq!(truncate_start("/tmp/a", 30), "/tmp/a");
+        let path = "/Users/test/Desktop/スクリーンショット 2024-01-01.png";
+        assert_eq!(
+            truncate_start(path, 30),
+            "...op/スクリーンショット 2024-01-01.png"
+        );
+        // Every cut position inside the multibyte name is safe
+        for max in 4..path.chars().count() {
+            assert_eq!(truncate_start(path, max).chars().count(), max);
+        }
+    }
+
+    #[test]
+    fn category_rows_show_the_item_count() {
+        let item = ScannedItem {
+            path: "/Users/test/.Trash/old.dmg".into(),
+            size: 800,
+            modified: SystemTime::UNIX_EPOCH,
+        };
+        let mut trash = ScanResult {
+            category: CategoryType::Trash,
+            total_size: 3200,
+            items: vec![item; 4],
+            is_selected: true,
+            root_path: "/Users/test/.Trash".into(),
+            roots: Vec::new(),
+            timed_out: false,
+            duration: Duration::ZERO,
+            offloaded: 0,
+            warnings: Vec::new(),
+            note: None,
+            in_use: Vec::new(),
+        };
+        assert_eq!(category_label(&trash, true), "[x] Trash             ★");
+        assert_eq!(item_count_label(trash.items.len()), "4 items");
+        trash.is_selected = false;
+        assert_eq!(category_label(&trash, false), "[ ] Trash              ");
+        assert_eq!(item_count_label(1), "1 item");
+        assert_eq!(item_count_label(12_345), "12,345 items");
+    }
+
+    #[test]
+    fn spinner_cycles_through_its_frames() {
+        let frames: Vec<&str> = (0..12).map(spinner_frame).collect();
+        assert_eq!(
+            frames,
+            vec!["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏", "⠋", "⠙"]
+        );
+    }
+
+    #[test]
+    fn size_bar_widths() {
+        assert_eq!(size_bar(0.0, 10), "░░░░░░░░░░");
+        assert_eq!(size_bar(0.5, 10), "█████░░░░░");
+        assert_eq!(size_bar(1.0, 10), "██████████");
+        assert_eq!(size_bar(1.5, 4), "████");
+    }
+
+    #[test]
+    fn header_shows_package_version() {
+        assert_eq!(
+            header_title(),
+            format!("sukkiri v{}", env!("CARGO_PKG_VERSION"))
+        );
+    }
+}
@@ -1,15 +1,37 @@
 pub mod app;
 pub mod components;
+pub mod theme;
 
-use crate::ui::app::{App, AppState};
+use crate::constants::COMPACT_WIDTH;
+use crate::report;
+use crate::ui::app::{App, AppState, Focus, View};
 use crate::ui::components::{
-    render_categories_list, render_details, render_footer, render_header, render_popup,
-    render_scanning,
+    render_categories_list, render_details, render_empty_state, render_footer, render_header,
+    render_popup, render_scanning, render_top_items,
 };
 use anyhow::Result;
-use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::prelude::*;
-use std::time::Duration;
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+/// How the categories view places the list and the details pane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MainLayout {
+    /// The list on the left, the details on the right.
+    SideBySide,
+    /// The list on top of the details, for narrow terminals.
+    Stacked,
+}
+
+/// Stacks the panes when the terminal is too narrow to fit them side by side.
+pub fn main_layout(width: u16) -> MainLayout {
+    if width < COMPACT_WIDTH {
+        MainLayout::Stacked
+    } else {
+        MainLayout::SideBySide
+    }
+}
 
 pub fn ui(f: &mut Frame, app: &mut App) {
     let chunks = Layout::default()
@@ -25,12 +47,23 @@ pub fn ui(f: &mut Frame, app: &mut App) {
 
     if let AppState::Scanning = app.state {
         render_scanning(f, app, chunks[1]);
+    } else if report::nothing_found(&app.results) {
+        render_empty_state(f, app, chunks[1]);
+    } else if app.view == View::TopItems {
+        render_top_items(f, app, chunks[1]);
     } else {
+        let direction = match main_layout(f.area().width) {
+            MainLayout::SideBySide => Direction::Horizontal,
+            MainLayout::Stacked => Direction::Vertical,
+        };
         let main_chunks = Layout::default()
-            .direction(Direction::Horizontal)
+            .direction(direction)
             .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
             .split(chunks[1]);
 
+        app.list_area = main_chunks[0];
+        app.details_area = main_chunks[1];
+
         render_categories_list(f, app, main_chunks[0]);
         render_details(f, app, main_chunks[1]);
     }
@@ -39,66 +72,145 @@ pub fn ui(f: &mut Frame, app: &mut App) {
     render_popup(f, app);
 }
 
+/// Raw mode turns Ctrl-C into a key press instead of SIGINT.
+fn is_ctrl_c(key: &KeyEvent) -> bool {
+    key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL)
+}
+
+/// Picks up the results of background cleaning and scanning, and refreshes what is due.
+/// Called once per tick of the event loop, which it counts for animations.
+fn poll_background(app: &mut App) {
+    app.tick = app.tick.wrapping_add(1);
+    if let AppState::Cleaning = app.state {
+        app.check_cleaning_status();
+    }
+    if let AppState::Scanning = app.state {
+        app.check_scan_status();
+    } else {
+        app.check_rescan_status();
+        app.check_duplicates_status();
+        app.check_expand_status();
+    }
+    if app.should_refresh(Instant::now()) {
+        app.refresh_all(Instant::now());
+    }
+    if app.should_refresh_disks(Instant::now()) {
+        app.refresh_disks(Instant::now());
+    }
+}
+
 pub fn run_app(
     terminal: &mut Terminal<CrosstermBackend<std::io::Stderr>>,
     app: &mut App,
 ) -> Result<()> {
     loop {
         terminal.draw(|f| ui(f, app))?;
-
-        // Check for async cleaning results
-        if let AppState::Cleaning = app.state {
-            app.check_cleaning_status();
-        }
-
-        // Check for scanning results
-        if let AppState::Scanning = app.state {
-            app.check_scan_status();
+        poll_background(app);
+        if app.handle_interrupt() {
+            return Ok(());
         }
 
         // Event polling with timeout to allow UI updates during Cleaning
-        if event::poll(Duration::from_millis(100))?
-            && let Event::Key(key) = event::read()?
-            && key.kind == KeyEventKind::Press
-        {
-            match app.state {
-                AppState::Browsing => match key.code {
-                    KeyCode::Char('q') => return Ok(()),
-                    KeyCode::Down | KeyCode::Char('j') => app.next(),
-                    KeyCode::Up | KeyCode::Char('k') => app.previous(),
-                    KeyCode::Char(' ') => app.toggle(),
-                    KeyCode::Char('a' | 'A') => app.toggle_all(),
-                    KeyCode::Enter => {
-                        if app.total_selected_size() > 0 {
-                            app.state = AppState::Confirming;
+        if event::poll(Duration::from_millis(100))? {
+            match event::read()? {
+                Event::Key(key) if is_ctrl_c(&key) => {
+                    app.interrupted.store(true, Ordering::Relaxed);
+                }
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    app.message = None;
+                    match app.state {
+                        AppState::Browsing if app.view == View::TopItems => match key.code {
+                            KeyCode::Char('q') if app.request_quit() => return Ok(()),
+                            KeyCode::Down | KeyCode::Char('j') => app.next_top_item(),
+                            KeyCode::Up | KeyCode::Char('k') => app.previous_top_item(),
+                            KeyCode::Char('t') | KeyCode::Esc => app.toggle_view(),
+                            KeyCode::Char('d') => app.toggle_delete_mode(),
+                            KeyCode::Char('o') => app.reveal_highlighted(),
+                            KeyCode::Char('y') => app.copy_highlighted(),
+                            KeyCode::Enter => app.stage_top_item(),
+                            _ => {}
+                        },
+                        AppState::Browsing => match key.code {
+                            KeyCode::Char('q') if app.request_quit() => return Ok(()),
+                            KeyCode::Down | KeyCode::Char('j') => app.navigate_down(),
+                            KeyCode::Up | KeyCode::Char('k') => app.navigate_up(),
+                            KeyCode::Tab => app.toggle_focus(),
+                            KeyCode::Char('s') if app.focus == Focus::Details => {
+                                app.cycle_item_sort();
+                            }
+                            KeyCode::Char('e') if app.focus == Focus::Details => {
+                                app.toggle_expand();
+                            }
+                            KeyCode::Right | KeyCode::Char('l') => app.focus = Focus::Details,
+                            KeyCode::Left | KeyCode::Char('h') => app.focus = Focus::Categories,
+                            KeyCode::Char(' ') => app.toggle(),
+                            KeyCode::Char('a' | 'A') => app.toggle_all(),
+                            KeyCode::Char('d') => app.toggle_delete_mode(),
+                            KeyCode::Char('t') => app.toggle_view(),
+                            KeyCode::Char('i') => app.toggle_installers_only(),
+                            KeyCode::Char('o') => app.reveal_highlighted(),
+                            KeyCode::Char('y') => app.copy_highlighted(),
+                            KeyCode::Char('u') => app.undo_last_clean(),
+                            KeyCode::Char('x') => app.stage_highlighted_category(),
+                            KeyCode::Char('p') => app.toggle_pin(),
+                            KeyCode::Char('r') => app.refresh_disks(Instant::now()),
+                            KeyCode::Enter if app.total_selected_size() > 0 => app.confirm(),
+                            _ => {}
+                        },
+                        AppState::Confirming => match key.code {
+                            KeyCode::Char('y') | KeyCode::Enter => app.clean_selected(),
+                            KeyCode::Char('n' | 'q') | KeyCode::Esc => app.cancel_confirm(),
+                            KeyCode::Down | KeyCode::Char('j') => app.move_review(1),
+                            KeyCode::Up | KeyCode::Char('k') => app.move_review(-1),
+                            KeyCode::Char(' ') => app.remove_from_plan(),
+                            _ => {}
+                        },
+                        AppState::Cleaning => {
+                            // Ignore text input while cleaning, but maybe allow force quit?
+                            // For safety let's just wait.
                         }
+                        AppState::Scanning => match key.code {
+                            KeyCode::Char('q') => return Ok(()),
+                            KeyCode::Esc => app.cancel_scan(),
+                            _ => {}
+                        },
+                        AppState::ConfirmQuit => match key.code {
+                            KeyCode::Char('y' | 'q') => return Ok(()),
+                            KeyCode::Char('n') | KeyCode::Esc => app.state = AppState::Browsing,
+                            _ => {}
+                        },
+                        AppState::Done(_) => match key.code {
+                            KeyCode::Char('r') if !app.denied.is_empty() => app.retry_denied(),
+                            KeyCode::Char('u') => app.undo_last_clean(),
+                            KeyCode::Esc | KeyCode::Enter | KeyCode::Char(' ' | 'q') => {
+                                app.dismiss_done();
+                            }
+                            _ => {}
+                        },
                     }
-                    _ => {}
-                },
-                AppState::Confirming => match key.code {
-                    KeyCode::Char('y') | KeyCode::Enter => app.clean_selected(),
-                    KeyCode::Char('n' | 'q') | KeyCode::Esc => {
-                        app.state = AppState::Browsing;
-                    }
-                    _ => {}
-                },
-                AppState::Cleaning => {
-                    // Ignore text input while cleaning, but maybe allow force quit?
-                    // For safety let's just wait.
                 }
-                AppState::Scanning => {
-                    if let KeyCode::Char('q') | KeyCode::Esc = key.code {
-                        // Allow early exit?
-                        return Ok(());
+                Event::Mouse(mouse) => {
+                    if let AppState::Browsing = app.state
+                        && app.view == View::Categories
+                    {
+                        app.handle_mouse(mouse);
                     }
                 }
-                AppState::Done(_) => match key.code {
-                    KeyCode::Esc | KeyCode::Enter | KeyCode::Char(' ' | 'q') => {
-                        app.state = AppState::Browsing;
-                    }
-                    _ => {}
-                },
+                _ => {}
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn narrow_terminals_stack_the_panes() {
+        assert_eq!(main_layout(60), MainLayout::Stacked);
+        assert_eq!(main_layout(COMPACT_WIDTH - 1), MainLayout::Stacked);
+        assert_eq!(main_layout(COMPACT_WIDTH), MainLayout::SideBySide);
+        assert_eq!(main_layout(200), MainLayout::SideBySide);
+    }
+}
@@ -0,0 +1,138 @@
+use crate::config::ThemeConfig;
+use clap::ValueEnum;
+use ratatui::style::Color;
+use serde::Deserialize;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemePreset {
+    Default,
+    Monochrome,
+    HighContrast,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub primary: Color,
+    pub secondary: Color,
+    pub accent: Color,
+    pub border: Color,
+    pub muted: Color,
+    pub success: Color,
+    pub warning: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::preset(ThemePreset::Default)
+    }
+}
+
+impl Theme {
+    pub const fn preset(preset: ThemePreset) -> Self {
+        match preset {
+            ThemePreset::Default => Self {
+                primary: Color::Cyan,
+                secondary: Color::Blue,
+                accent: Color::Magenta,
+                border: Color::DarkGray,
+                muted: Color::DarkGray,
+                success: Color::Green,
+                warning: Color::Yellow,
+            },
+            ThemePreset::Monochrome => Self {
+                primary: Color::White,
+                secondary: Color::Gray,
+                accent: Color::White,
+                border: Color::Gray,
+                muted: Color::Gray,
+                success: Color::White,
+                warning: Color::White,
+            },
+            ThemePreset::HighContrast => Self {
+                primary: Color::LightYellow,
+                secondary: Color::LightBlue,
+                accent: Color::LightMagenta,
+                border: Color::White,
+                muted: Color::Gray,
+                success: Color::LightGreen,
+                warning: Color::LightRed,
+            },
+        }
+    }
+
+    /// Builds the theme from the `[theme]` config section.
+    /// A preset given on the command line takes precedence over the configured one,
+    /// and colors that are unset or invalid keep the preset's value.
+    pub fn from_config(config: &ThemeConfig, preset_override: Option<ThemePreset>) -> Self {
+        let preset = preset_override
+            .or(config.preset)
+            .unwrap_or(ThemePreset::Default);
+        let mut theme = Self::preset(preset);
+
+        let overrides = [
+            (&config.primary, &mut theme.primary),
+            (&config.secondary, &mut theme.secondary),
+            (&config.accent, &mut theme.accent),
+            (&config.border, &mut theme.border),
+            (&config.muted, &mut theme.muted),
+            (&config.success, &mut theme.success),
+            (&config.warning, &mut theme.warning),
+        ];
+        for (value, color) in overrides {
+            if let Some(parsed) = value.as_deref().and_then(parse_color) {
+                *color = parsed;
+            }
+        }
+
+        theme
+    }
+}
+
+/// Parses a color name (`"cyan"`, `"light-blue"`) or hex value (`"#00ffaa"`).
+pub fn parse_color(value: &str) -> Option<Color> {
+    Color::from_str(value.trim()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_color_values() {
+        assert_eq!(parse_color("cyan"), Some(Color::Cyan));
+        assert_eq!(parse_color("Light-Blue"), Some(Color::LightBlue));
+        assert_eq!(parse_color(" #ff8800 "), Some(Color::Rgb(255, 136, 0)));
+        assert_eq!(parse_color("not-a-color"), None);
+        assert_eq!(parse_color("#zzzzzz"), None);
+    }
+
+    #[test]
+    fn from_config_overrides_preset() {
+        let config = ThemeConfig {
+            preset: Some(ThemePreset::Monochrome),
+            primary: Some("#102030".to_string()),
+            accent: Some("bogus".to_string()),
+            ..ThemeConfig::default()
+        };
+
+        let theme = Theme::from_config(&config, None);
+        assert_eq!(theme.primary, Color::Rgb(16, 32, 48));
+        // Invalid values fall back to the preset
+        assert_eq!(theme.accent, Theme::preset(ThemePreset::Monochrome).accent);
+
+        let theme = Theme::from_config(&config, Some(ThemePreset::HighContrast));
+        assert_eq!(
+            theme.border,
+            Theme::preset(ThemePreset::HighContrast).border
+        );
+    }
+
+    #[test]
+    fn unset_config_uses_default_colors() {
+        let theme = Theme::from_config(&ThemeConfig::default(), None);
+        assert_eq!(theme, Theme::default());
+        assert_eq!(theme.primary, Color::Cyan);
+    }
+}
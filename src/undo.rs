@@ -0,0 +1,146 @@
+use crate::config::config_dir;
+use anyhow::Result;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File in the configuration directory recording what the last clean moved to the Trash.
+const UNDO_LOG_FILE: &str = "last_clean.toml";
+
+/// A file moved to the Trash and where it came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrashedFile {
+    pub original: PathBuf,
+    pub trashed: PathBuf,
+}
+
+/// The files moved to the Trash by the last clean.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UndoLog {
+    #[serde(default)]
+    pub files: Vec<TrashedFile>,
+}
+
+impl UndoLog {
+    pub fn parse(content: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(content)
+    }
+
+    pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string(self)
+    }
+
+    fn load(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(content) => Ok(Self::parse(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_toml()?)?;
+        Ok(())
+    }
+}
+
+fn log_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join(UNDO_LOG_FILE))
+}
+
+/// Replaces the undo log with the files trashed by the clean that just finished.
+pub fn record_last_clean(files: Vec<TrashedFile>) {
+    let Some(path) = log_path() else {
+        return;
+    };
+    if let Err(e) = (UndoLog { files }).save(&path) {
+        warn!("Failed to write {}: {e}", path.display());
+    }
+}
+
+/// Moves the files of the last clean back out of the Trash.
+/// Returns how many were restored; files that couldn't be stay in the log.
+pub fn undo_last_clean() -> Result<usize> {
+    let path = log_path().ok_or_else(|| anyhow::anyhow!("No configuration directory"))?;
+    let log = UndoLog::load(&path)?;
+    let total = log.files.len();
+    let remaining = restore(log.files);
+    let restored = total - remaining.len();
+    UndoLog { files: remaining }.save(&path)?;
+    Ok(restored)
+}
+
+/// Moves each trashed file back to its original location.
+/// Returns the files that couldn't be restored.
+pub fn restore(files: Vec<TrashedFile>) -> Vec<TrashedFile> {
+    files
+        .into_iter()
+        .filter(|file| {
+            if !file.trashed.exists() {
+                warn!("{} is no longer in the Trash", file.trashed.display());
+                return false;
+            }
+            if file.original.exists() {
+                warn!("Not restoring over existing {}", file.original.display());
+                return true;
+            }
+            let result = file
+                .original
+                .parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|()| fs::rename(&file.trashed, &file.original));
+            match result {
+                Ok(()) => {
+                    info!("Restored {}", file.original.display());
+                    false
+                }
+                Err(e) => {
+                    warn!("Failed to restore {}: {e}", file.original.display());
+                    true
+                }
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undo_log_round_trips() {
+        let log = UndoLog {
+            files: vec![TrashedFile {
+                original: PathBuf::from("/Users/test/Downloads/Tool.dmg"),
+                trashed: PathBuf::from("/Users/test/.Trash/Tool 2.dmg"),
+            }],
+        };
+        let content = log.to_toml().unwrap();
+        assert_eq!(UndoLog::parse(&content).unwrap(), log);
+        assert_eq!(UndoLog::parse("").unwrap(), UndoLog::default());
+    }
+
+    #[test]
+    fn restore_moves_files_back() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let trash = dir.path().join(".Trash");
+        fs::create_dir(&trash)?;
+        let trashed = trash.join("report.txt");
+        fs::write(&trashed, b"x")?;
+        let original = dir.path().join("docs/report.txt");
+
+        let remaining = restore(vec![TrashedFile {
+            original: original.clone(),
+            trashed: trashed.clone(),
+        }]);
+
+        assert!(remaining.is_empty());
+        assert!(original.exists());
+        assert!(!trashed.exists());
+        Ok(())
+    }
+}
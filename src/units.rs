@@ -0,0 +1,60 @@
+use clap::ValueEnum;
+use humansize::{BINARY, DECIMAL, FormatSizeOptions, format_size};
+use serde::Deserialize;
+
+/// How byte counts are displayed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Units {
+    /// Powers of 1024 (KiB, MiB, GiB)
+    #[default]
+    Binary,
+    /// Powers of 1000 (kB, MB, GB), as shown by Finder
+    Decimal,
+}
+
+impl Units {
+    fn options(self) -> FormatSizeOptions {
+        match self {
+            Self::Binary => BINARY,
+            Self::Decimal => DECIMAL,
+        }
+    }
+
+    pub fn format(self, bytes: u64) -> String {
+        format_size(bytes, self.options())
+    }
+}
+
+/// Formats a count with thousands separators, e.g. `1,204`.
+pub fn format_count(count: usize) -> String {
+    let digits = count.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(digit);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binary_and_decimal_formatting() {
+        let bytes = 1_500_000_000;
+        assert_eq!(Units::Binary.format(bytes), "1.40 GiB");
+        assert_eq!(Units::Decimal.format(bytes), "1.50 GB");
+    }
+
+    #[test]
+    fn counts_get_thousands_separators() {
+        assert_eq!(format_count(0), "0");
+        assert_eq!(format_count(999), "999");
+        assert_eq!(format_count(1_204), "1,204");
+        assert_eq!(format_count(12_345_678), "12,345,678");
+    }
+}
@@ -0,0 +1,45 @@
+use std::fs;
+use std::process::Command;
+
+/// Runs `sukkiri audit --json` for the user caches of a fixture home.
+fn audit_user_cache(home: &std::path::Path, config_dir: &std::path::Path) -> serde_json::Value {
+    let output = Command::new(env!("CARGO_BIN_EXE_sukkiri"))
+        .args([
+            "--only",
+            "user-cache",
+            "--no-external-commands",
+            "audit",
+            "--json",
+        ])
+        .env("HOME", home)
+        .env_remove("SUDO_USER")
+        .env("SUKKIRI_CONFIG_DIR", config_dir)
+        .output()
+        .expect("sukkiri runs");
+    assert!(output.status.success());
+    serde_json::from_slice(&output.stdout).expect("audit prints JSON")
+}
+
+#[test]
+fn allowlist_is_read_from_the_configured_directory() -> anyhow::Result<()> {
+    let home = tempfile::tempdir()?;
+    let config_dir = tempfile::tempdir()?;
+    let cache = home.path().join("Library/Caches/com.example.app");
+    fs::create_dir_all(&cache)?;
+    fs::write(cache.join("blob"), b"cached")?;
+
+    assert_eq!(
+        audit_user_cache(home.path(), config_dir.path())["total_items"],
+        1
+    );
+
+    fs::write(
+        config_dir.path().join("allowlist.txt"),
+        format!("{}\n", cache.display()),
+    )?;
+    assert_eq!(
+        audit_user_cache(home.path(), config_dir.path())["total_items"],
+        0
+    );
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::process::Command;
+
+#[test]
+fn categories_left_out_run_no_commands() -> anyhow::Result<()> {
+    let home = tempfile::tempdir()?;
+    let bin = tempfile::tempdir()?;
+    let ran = bin.path().join("ran");
+    // Stand-ins that record being called instead of asking the system
+    for name in ["defaults", "getconf"] {
+        let script = bin.path().join(name);
+        fs::write(
+            &script,
+            format!("#!/bin/sh\necho {name} >> '{}'\n", ran.display()),
+        )?;
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755))?;
+    }
+
+    let output = Command::new(env!("CARGO_BIN_EXE_sukkiri"))
+        .args(["--only", "trash", "audit", "--json"])
+        .env("HOME", home.path())
+        .env("PATH", bin.path())
+        .env("SUKKIRI_CONFIG_DIR", home.path().join("config"))
+        .env_remove("SUDO_USER")
+        .output()?;
+    assert!(output.status.success());
+    assert!(!ran.exists(), "ran {}", fs::read_to_string(&ran)?);
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use std::collections::HashSet;
+use std::fs;
+use std::time::Duration;
+use sukkiri::{Allowlist, CategoryFilter, CategoryType, ScanOptions};
+
+#[test]
+fn scan_all_finds_node_modules_in_a_fixture() -> anyhow::Result<()> {
+    let projects = tempfile::tempdir()?;
+    let write = |path: &str, size: usize| -> anyhow::Result<()> {
+        let path = projects.path().join(path);
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, vec![0u8; size])?;
+        Ok(())
+    };
+    write("web/node_modules/react/index.js", 4096)?;
+    write("web/node_modules/react/tiny.js", 10)?;
+    write("api/node_modules/express/index.js", 100)?;
+    write("keep/node_modules/lib/index.js", 8192)?;
+    write("web/src/main.js", 1)?;
+
+    let options = ScanOptions {
+        allowlist: Allowlist::new(vec![projects.path().join("keep").display().to_string()]),
+        project_roots: vec![projects.path().to_path_buf()],
+        categories: CategoryFilter::Only(HashSet::from([CategoryType::NodeModules])),
+        ..ScanOptions::default()
+    };
+    let results = sukkiri::scan_all(&options)?;
+
+    assert_eq!(results.len(), 1);
+    let node_modules = &results[0];
+    assert_eq!(node_modules.category, CategoryType::NodeModules);
+    assert_eq!(node_modules.total_size, 4096 + 10 + 100);
+    let found: Vec<_> = node_modules.items.iter().map(|i| i.path.clone()).collect();
+    assert_eq!(
+        found,
+        vec![
+            projects.path().join("web/node_modules"),
+            projects.path().join("api/node_modules")
+        ]
+    );
+
+    // Size and age filters drop items after scanning
+    let filtered = sukkiri::scan_all(&ScanOptions {
+        min_size: 1024,
+        ..options.clone()
+    })?;
+    assert_eq!(filtered[0].total_size, 4096 + 10);
+    let old_only = sukkiri::scan_all(&ScanOptions {
+        min_age: Some(Duration::from_hours(1)),
+        ..options
+    })?;
+    assert!(old_only[0].items.is_empty());
+    Ok(())
+}